@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use invocation_counter::{InvocationCounter, StaticInvocationCounter};
+use std::hint::black_box;
+
+fn count_in(c: &mut Criterion) {
+    // 16 slots (2^4) * 16 time units (2^4) = 256 time units window
+    let boxed = InvocationCounter::new(4, 4);
+    let fixed = StaticInvocationCounter::<16>::new(4);
+    for time in 0..1_000 {
+        boxed.register(time);
+        fixed.register(time);
+    }
+
+    let mut group = c.benchmark_group("count_in");
+    group.bench_function("boxed/16", |b| {
+        b.iter(|| boxed.count_in(black_box(800), black_box(1_000)))
+    });
+    group.bench_function("static/16", |b| {
+        b.iter(|| fixed.count_in(black_box(800), black_box(1_000)))
+    });
+    group.finish();
+}
+
+fn count_in_by_width(c: &mut Criterion) {
+    // 1024 slots (2^10) * 16 time units (2^4) = 16384 time units window
+    let counter = InvocationCounter::new(10, 4);
+    for time in 0..20_000 {
+        counter.register(time);
+    }
+
+    // Narrow ranges only visit the slots they cover, wide ones scan every slot
+    let mut group = c.benchmark_group("count_in_by_width");
+    for intervals in [4, 64, 255, 256, 1024] {
+        let start = 20_000 - intervals * 16;
+        group.bench_function(format!("1024/{intervals}"), |b| {
+            b.iter(|| counter.count_in(black_box(start), black_box(20_000)))
+        });
+    }
+    group.finish();
+}
+
+fn count_in_full_scan(c: &mut Criterion) {
+    // 65536 slots (2^16) * 1 time unit (2^0) = 65536 time units window
+    let counter = InvocationCounter::new(16, 0);
+    for time in 0..100_000 {
+        counter.register(time);
+    }
+
+    // Run once as is and once with `--features simd` to compare the scalar scan with the
+    // vectorized one
+    let path = if cfg!(feature = "simd") {
+        "simd"
+    } else {
+        "scalar"
+    };
+    let mut group = c.benchmark_group("count_in_full_scan");
+    group.bench_function(format!("{path}/65536"), |b| {
+        b.iter(|| counter.count_in(black_box(0), black_box(100_000)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, count_in, count_in_by_width, count_in_full_scan);
+criterion_main!(benches);
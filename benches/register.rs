@@ -0,0 +1,138 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use invocation_counter::{InvocationCounter, OrderingProfile};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A minimal stand-in for the crate's slot, without cache line padding.
+#[derive(Default)]
+struct UnpaddedSlot {
+    interval_start: AtomicU64,
+    counter: AtomicU32,
+}
+
+impl UnpaddedSlot {
+    fn register(&self, interval_start: u64) {
+        if self.interval_start.load(Ordering::Acquire) != interval_start {
+            self.interval_start.store(interval_start, Ordering::Release);
+            self.counter.store(0, Ordering::Release);
+        }
+        self.counter.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// The same slot padded to a cache line, like the crate's.
+#[derive(Default)]
+#[repr(align(64))]
+struct PaddedSlot(UnpaddedSlot);
+
+/// Runs one thread per slot, each registering `iters` times into its own slot with the time
+/// equal to the slot's index, and returns the elapsed time.
+fn register_neighbouring<S: Sync>(
+    slots: &[S],
+    iters: u64,
+    register: impl Fn(&S, u64) + Sync,
+) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for (time, slot) in slots.iter().enumerate() {
+            let register = &register;
+            scope.spawn(move || {
+                for _ in 0..iters {
+                    register(slot, black_box(time as u64));
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn register(c: &mut Criterion) {
+    // 64 slots (2^6) * 16 time units (2^4) = 1024 time units window
+    let counter = InvocationCounter::new(6, 4);
+
+    let mut group = c.benchmark_group("register");
+    group.bench_function("sequential", |b| {
+        let mut time = 0u64;
+        b.iter(|| {
+            time += 1;
+            counter.register(black_box(time));
+        })
+    });
+    group.finish();
+}
+
+fn register_concurrent(c: &mut Criterion) {
+    const THREADS: u64 = 4;
+
+    // 64 slots (2^6) * 1 time unit (2^0) = 64 time units window
+    let counter = InvocationCounter::new(6, 0);
+    // Every thread keeps registering into its own interval, and the intervals map to
+    // neighbouring slots: without cache line padding, those slots share a line and the threads
+    // keep stealing it from each other. The padded and unpadded stand-ins run the same loop on
+    // bare slots to show the difference padding makes.
+    let mut group = c.benchmark_group("register");
+    group.bench_function("concurrent_neighbouring_slots", |b| {
+        b.iter_custom(|iters| {
+            let threads = [(); THREADS as usize];
+            register_neighbouring(&threads, iters, |_, time| counter.register(time))
+        })
+    });
+    group.bench_function("concurrent_neighbouring_slots_padded", |b| {
+        let slots: Vec<PaddedSlot> = (0..THREADS).map(|_| PaddedSlot::default()).collect();
+        b.iter_custom(|iters| {
+            register_neighbouring(&slots, iters, |slot, time| slot.0.register(time))
+        })
+    });
+    group.bench_function("concurrent_neighbouring_slots_unpadded", |b| {
+        let slots: Vec<UnpaddedSlot> = (0..THREADS).map(|_| UnpaddedSlot::default()).collect();
+        b.iter_custom(|iters| register_neighbouring(&slots, iters, UnpaddedSlot::register))
+    });
+    group.finish();
+}
+
+fn register_concurrent_profiles(c: &mut Criterion) {
+    const THREADS: u64 = 4;
+    const REGISTRATIONS_PER_QUERY: u64 = 16;
+
+    // Every thread registers at increasing times, rolling slots over as it goes, and runs a
+    // query every few registrations: the mix exercises every ordering a profile sets
+    let mut group = c.benchmark_group("register");
+    for (name, profile) in [
+        ("concurrent_strict", OrderingProfile::Strict),
+        ("concurrent_fast", OrderingProfile::Fast),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter_custom(|iters| {
+                // 64 slots (2^6) * 16 time units (2^4) = 1024 time units window
+                let counter = InvocationCounter::new(6, 4).with_ordering_profile(profile);
+                let start = Instant::now();
+                thread::scope(|scope| {
+                    for thread in 0..THREADS {
+                        let counter = &counter;
+                        scope.spawn(move || {
+                            for i in 0..iters {
+                                let time = i * THREADS + thread;
+                                counter.register(black_box(time));
+                                if i % REGISTRATIONS_PER_QUERY == 0 {
+                                    black_box(counter.count_last(black_box(512)));
+                                }
+                            }
+                        });
+                    }
+                });
+                start.elapsed()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    register,
+    register_concurrent,
+    register_concurrent_profiles
+);
+criterion_main!(benches);
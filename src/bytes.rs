@@ -0,0 +1,154 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+
+use crate::{CounterError, InvocationCounter};
+
+/// Size of the encoded `max_current_time`.
+const TIME_LEN: usize = 8;
+/// Size of an encoded slot: `interval_start` then `counter`.
+const SLOT_LEN: usize = 8 + 4;
+
+impl InvocationCounter {
+    /// Serializes the window data of this counter without any geometry header.
+    ///
+    /// The output is the latest observed time followed by every slot's interval start and
+    /// count, all little-endian, in ring order: `8 + 12 × 2^slot_count_exp` bytes. The slot
+    /// exponents aren't written, which saves space when a whole fleet of counters shares the
+    /// same geometry; decode with [`from_bytes_dense`](Self::from_bytes_dense) and the same
+    /// exponents.
+    ///
+    /// Settings, the lifetime count and the spillover aren't encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// counter.register(10);
+    ///
+    /// let bytes = counter.to_bytes_dense();
+    /// assert_eq!(bytes.len(), 8 + 12 * 8);
+    ///
+    /// let decoded = InvocationCounter::from_bytes_dense(3, 4, &bytes).unwrap();
+    /// assert_eq!(decoded.count_in(0, 16), 1);
+    /// ```
+    pub fn to_bytes_dense(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(TIME_LEN + SLOT_LEN * self.slots.len());
+
+        bytes.extend_from_slice(&self.max_current_time.load(Ordering::Acquire).to_le_bytes());
+        for slot in &self.slots {
+            let (interval_start, count) = slot.load(&self.orderings().count_in);
+            bytes.extend_from_slice(&interval_start.to_le_bytes());
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Rebuilds a counter from the output of [`to_bytes_dense`](Self::to_bytes_dense), given
+    /// the geometry of the counter that produced it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::Decode`] if the length of `bytes` doesn't match
+    /// `2^slot_count_exp` slots, or if a slot holds an interval that isn't aligned to
+    /// `2^slot_size_exp` or that doesn't map to that slot.
+    pub fn from_bytes_dense(
+        slot_count_exp: u8,
+        slot_size_exp: u8,
+        bytes: &[u8],
+    ) -> Result<Self, CounterError> {
+        let expected_len = 1usize
+            .checked_shl(slot_count_exp.into())
+            .and_then(|slot_count| slot_count.checked_mul(SLOT_LEN))
+            .and_then(|slots_len| slots_len.checked_add(TIME_LEN));
+        if expected_len != Some(bytes.len()) {
+            return Err(CounterError::Decode {
+                reason: format!(
+                    "expected {} bytes for 2^{slot_count_exp} slots, found {}",
+                    expected_len.map_or_else(|| "too many".to_string(), |len| len.to_string()),
+                    bytes.len()
+                ),
+            });
+        }
+
+        let (time, slots) = bytes.split_at(TIME_LEN);
+        let slots = slots.chunks_exact(SLOT_LEN).map(|chunk| {
+            let (interval_start, count) = chunk.split_at(8);
+            (
+                u64::from_le_bytes(interval_start.try_into().unwrap()),
+                u32::from_le_bytes(count.try_into().unwrap()),
+            )
+        });
+
+        Self::from_parts(
+            slot_count_exp,
+            slot_size_exp,
+            u64::from_le_bytes(time.try_into().unwrap()),
+            slots,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_round_trip() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for time in [1, 3, 9, 17, 25, 30, 33, 41] {
+            counter.register(time);
+        }
+
+        let bytes = counter.to_bytes_dense();
+        assert_eq!(bytes.len(), 8 + 12 * 8);
+
+        let decoded = InvocationCounter::from_bytes_dense(3, 2, &bytes).unwrap();
+        for start in 0..64 {
+            for end in start..64 {
+                assert_eq!(decoded.count_in(start, end), counter.count_in(start, end));
+            }
+        }
+        assert_eq!(decoded.to_bytes_dense(), bytes);
+    }
+
+    #[test]
+    fn test_dense_length_mismatch() {
+        let bytes = InvocationCounter::new(3, 2).to_bytes_dense();
+
+        assert_eq!(
+            InvocationCounter::from_bytes_dense(4, 2, &bytes).unwrap_err(),
+            CounterError::Decode {
+                reason: "expected 200 bytes for 2^4 slots, found 104".to_string()
+            }
+        );
+        assert!(InvocationCounter::from_bytes_dense(3, 2, &bytes[1..]).is_err());
+        assert!(InvocationCounter::from_bytes_dense(3, 2, &[]).is_err());
+        assert!(InvocationCounter::from_bytes_dense(u8::MAX, 2, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_dense_invalid_interval() {
+        let counter = InvocationCounter::new(3, 2);
+        counter.register(5);
+
+        let bytes = counter.to_bytes_dense();
+        // Slot 1 holds the interval starting at 4, which isn't a multiple of 8
+        assert!(InvocationCounter::from_bytes_dense(3, 3, &bytes).is_err());
+
+        // With 2 slots of 4 units, the interval starting at 4 belongs in slot 1, not 0
+        let mut misplaced = InvocationCounter::new(1, 2).to_bytes_dense();
+        misplaced[8..16].copy_from_slice(&4u64.to_le_bytes());
+        misplaced[16..20].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            InvocationCounter::from_bytes_dense(1, 2, &misplaced).unwrap_err(),
+            CounterError::Decode {
+                reason: "interval start 4 found in slot 0".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::ops::Range;
+use std::sync::Mutex;
+
+use crate::{aligned_range, InvocationCounter};
+
+/// A cached result of `count_in` over a slot-aligned range.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    range: Range<u64>,
+    generation: u64,
+    count: u32,
+}
+
+/// An [`InvocationCounter`] wrapper that caches the results of [`count_in`](Self::count_in)
+/// for several distinct ranges.
+///
+/// Dashboards typically poll a few fixed windows (e.g. last 1m, 5m and 15m) much more often
+/// than the counter changes. This wrapper keeps the most recently used ranges in a small LRU
+/// cache, keyed by the slot-aligned range, so repeated polls of the same ranges scan the slots
+/// once per range until something changes.
+///
+/// Every mutation of the counter bumps its [generation](InvocationCounter::generation), which
+/// invalidates the whole cache at once.
+///
+/// Only available with the `std` feature.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{CachedCounter, InvocationCounter};
+/// let counter = CachedCounter::new(InvocationCounter::new(6, 10), 3);
+///
+/// counter.counter().register(5_000);
+///
+/// let now = 60_000;
+/// for window in [1_000, 5_000, 15_000] {
+///     counter.count_in(now - window, now); // Scans the slots
+///     counter.count_in(now - window, now); // Served from the cache
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CachedCounter {
+    counter: InvocationCounter,
+    capacity: usize,
+    /// Least recently used entry first
+    entries: Mutex<Vec<CacheEntry>>,
+    #[cfg(test)]
+    scans: std::sync::atomic::AtomicUsize,
+}
+
+impl CachedCounter {
+    /// Wraps `counter`, caching the results of up to `capacity` distinct ranges.
+    ///
+    /// A `capacity` of `0` disables caching.
+    pub fn new(counter: InvocationCounter, capacity: usize) -> Self {
+        Self {
+            counter,
+            capacity,
+            entries: Mutex::new(Vec::with_capacity(capacity)),
+            #[cfg(test)]
+            scans: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the wrapped counter, e.g. to register invocations.
+    pub fn counter(&self) -> &InvocationCounter {
+        &self.counter
+    }
+
+    /// Returns the wrapped counter, dropping the cache.
+    pub fn into_inner(self) -> InvocationCounter {
+        self.counter
+    }
+
+    /// Returns the maximum number of distinct ranges cached at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the total number of invocations within the specified time range.
+    ///
+    /// Returns the same value as [`InvocationCounter::count_in`], but reuses a previous result
+    /// for the same slot-aligned range if the counter didn't change since it was computed.
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let range = aligned_range(self.counter.slot_size_exp, start_time, end_time);
+        let generation = self.counter.generation();
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(index) = entries.iter().position(|entry| entry.range == range) {
+            let entry = entries.remove(index);
+            if entry.generation == generation {
+                let count = entry.count;
+                entries.push(entry);
+                return count;
+            }
+        }
+
+        #[cfg(test)]
+        self.scans
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let count = self.counter.count_in(range.start, range.end);
+
+        if self.capacity > 0 {
+            if entries.len() >= self.capacity {
+                entries.remove(0);
+            }
+            entries.push(CacheEntry {
+                range,
+                generation,
+                count,
+            });
+        }
+
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    fn scans(counter: &CachedCounter) -> usize {
+        counter.scans.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn test_repeated_polls_scan_once_per_range() {
+        // 16 slots (2^4) * 8 time units (2^3) = 128 time units window
+        let cached = CachedCounter::new(InvocationCounter::new(4, 3), 3);
+        for time in [3, 20, 50, 90, 120] {
+            cached.counter().register(time);
+        }
+
+        let ranges = [(112, 128), (64, 128), (0, 128)];
+        for _ in 0..10 {
+            for (start, end) in ranges {
+                assert_eq!(
+                    cached.count_in(start, end),
+                    cached.counter().count_in(start, end)
+                );
+            }
+        }
+        assert_eq!(scans(&cached), 3);
+
+        // Ranges aligning to the same slots share an entry
+        assert_eq!(cached.count_in(113, 127), 1);
+        assert_eq!(scans(&cached), 3);
+
+        // Any mutation invalidates every range
+        cached.counter().register(125);
+        for (start, end) in ranges {
+            assert_eq!(
+                cached.count_in(start, end),
+                cached.counter().count_in(start, end)
+            );
+            cached.count_in(start, end);
+        }
+        assert_eq!(scans(&cached), 6);
+
+        // Advancing time changes the window, so it invalidates too
+        cached.counter().advance_time(200);
+        assert_eq!(cached.count_in(0, 128), 3);
+        assert_eq!(scans(&cached), 7);
+    }
+
+    #[test]
+    fn test_least_recently_used_range_is_evicted() {
+        let cached = CachedCounter::new(InvocationCounter::new(4, 3), 2);
+        cached.counter().register(10);
+
+        cached.count_in(0, 8);
+        cached.count_in(8, 16);
+        cached.count_in(0, 8); // [0, 8) is now the most recently used
+        assert_eq!(scans(&cached), 2);
+
+        cached.count_in(16, 24); // Evicts [8, 16)
+        cached.count_in(0, 8);
+        assert_eq!(scans(&cached), 3);
+        cached.count_in(8, 16);
+        assert_eq!(scans(&cached), 4);
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_caching() {
+        let cached = CachedCounter::new(InvocationCounter::new(4, 3), 0);
+        cached.counter().register(10);
+
+        assert_eq!(cached.count_in(0, 16), 1);
+        assert_eq!(cached.count_in(0, 16), 1);
+        assert_eq!(scans(&cached), 2);
+        assert_eq!(cached.capacity(), 0);
+        assert_eq!(cached.into_inner().count_in(0, 16), 1);
+    }
+}
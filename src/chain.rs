@@ -0,0 +1,160 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{CounterError, InvocationCounter};
+
+/// A sequence of [`InvocationCounter`]s covering consecutive time ranges, queried as a single
+/// timeline.
+///
+/// This fits tiered retention, where a coarse counter covers old data and a fine one covers
+/// recent data: [`count_in`](Self::count_in) splits the query at the boundaries between
+/// counters and sums what each counter holds for its own part of the range.
+///
+/// Each counter is attached with the range it covers. Ranges must be ascending, must not
+/// overlap, and must start and end on a slot boundary of their counter, so that splitting a
+/// query never counts an interval twice.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{CounterChain, InvocationCounter};
+/// let old = InvocationCounter::new(2, 6); // 4 slots × 64 units
+/// let recent = InvocationCounter::new(4, 2); // 16 slots × 4 units
+/// old.register(100);
+/// recent.register(250);
+///
+/// let mut chain = CounterChain::new();
+/// chain.push(0..192, old).unwrap();
+/// chain.push(192..256, recent).unwrap();
+///
+/// assert_eq!(chain.count_in(64, 256), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct CounterChain {
+    counters: Vec<(Range<u64>, InvocationCounter)>,
+}
+
+impl CounterChain {
+    /// Creates an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `counter`, answering queries for the time range `covers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidArgument`] if `covers` is empty, starts before the end of
+    /// the previously pushed range, or isn't aligned to the slot size of `counter`.
+    pub fn push(
+        &mut self,
+        covers: Range<u64>,
+        counter: InvocationCounter,
+    ) -> Result<(), CounterError> {
+        if covers.is_empty() {
+            return Err(CounterError::InvalidArgument {
+                reason: "the covered range is empty",
+            });
+        }
+        if let Some((previous, _)) = self.counters.last() {
+            if covers.start < previous.end {
+                return Err(CounterError::InvalidArgument {
+                    reason: "the covered range overlaps or precedes the previous one",
+                });
+            }
+        }
+        let slot_size_mask = (1 << counter.slot_size_exp()) - 1;
+        if covers.start & slot_size_mask != 0 || covers.end & slot_size_mask != 0 {
+            return Err(CounterError::InvalidArgument {
+                reason: "the covered range isn't aligned to the counter's slot size",
+            });
+        }
+
+        self.counters.push((covers, counter));
+        Ok(())
+    }
+
+    /// Returns the counter at `index` in the chain along with the range it covers.
+    pub fn get(&self, index: usize) -> Option<(&Range<u64>, &InvocationCounter)> {
+        self.counters
+            .get(index)
+            .map(|(covers, counter)| (covers, counter))
+    }
+
+    /// Returns the number of counters in the chain.
+    pub fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns `true` if the chain holds no counter.
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Returns the total number of invocations within the specified time range, across every
+    /// counter of the chain.
+    ///
+    /// The range is intersected with the range covered by each counter, and each non-empty
+    /// intersection is answered by that counter like [`InvocationCounter::count_in`]. Time
+    /// not covered by any counter contributes nothing.
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u64 {
+        self.counters
+            .iter()
+            .map(|(covers, counter)| {
+                let start = start_time.max(covers.start);
+                let end = end_time.min(covers.end);
+                counter.count_in_u64(start, end)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_spanning_counters() {
+        // Coarse: 4 slots (2^2) * 32 time units (2^5), holding [0, 128)
+        let coarse = InvocationCounter::new(2, 5);
+        // Fine: 8 slots (2^3) * 4 time units (2^2), holding [128, 160)
+        let fine = InvocationCounter::new(3, 2);
+        for time in [5, 40, 70, 100, 127] {
+            coarse.register(time);
+        }
+        for time in [128, 130, 141, 159] {
+            fine.register(time);
+        }
+
+        let mut chain = CounterChain::new();
+        chain.push(0..128, coarse).unwrap();
+        chain.push(128..160, fine).unwrap();
+        assert_eq!(chain.len(), 2);
+
+        assert_eq!(chain.count_in(0, 160), 9);
+        // Spanning both: [96, 128) from the coarse counter and [128, 144) from the fine one
+        assert_eq!(chain.count_in(100, 142), 2 + 3);
+        // Within a single counter, with its own alignment
+        assert_eq!(chain.count_in(130, 135), 2);
+        assert_eq!(chain.count_in(33, 34), 1);
+        // Outside every counter
+        assert_eq!(chain.count_in(160, 1_000), 0);
+
+        let (covers, fine) = chain.get(1).unwrap();
+        assert_eq!(covers, &(128..160));
+        assert_eq!(fine.count_in(128, 160), 4);
+    }
+
+    #[test]
+    fn test_push_validates_ranges() {
+        let mut chain = CounterChain::new();
+        assert!(chain.is_empty());
+
+        assert!(chain.push(16..16, InvocationCounter::new(2, 2)).is_err());
+        assert!(chain.push(2..16, InvocationCounter::new(2, 2)).is_err());
+        chain.push(16..32, InvocationCounter::new(2, 2)).unwrap();
+        assert!(chain.push(24..48, InvocationCounter::new(2, 2)).is_err());
+        chain.push(64..128, InvocationCounter::new(2, 3)).unwrap();
+        assert_eq!(chain.len(), 2);
+    }
+}
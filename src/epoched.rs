@@ -0,0 +1,123 @@
+use alloc::boxed::Box;
+
+use crate::InvocationCounter;
+
+/// A fixed set of [`InvocationCounter`]s sharing the same time axis, one per epoch.
+///
+/// Registrations are tagged with an epoch id, so concurrent experiments (e.g. A/B variants)
+/// are counted separately, and all events of one epoch can be dropped at once with
+/// [`drop_epoch`](Self::drop_epoch) without affecting the others. Epoch ids are indices in
+/// `0..epoch_count`.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::EpochedCounter;
+/// let counter = EpochedCounter::new(2, 3, 4);
+///
+/// counter.register(0, 10);
+/// counter.register(1, 12);
+/// counter.register(1, 14);
+///
+/// assert_eq!(counter.count_in(0, 0, 16), 1);
+/// assert_eq!(counter.count_in(1, 0, 16), 2);
+///
+/// counter.drop_epoch(1);
+/// assert_eq!(counter.count_in(1, 0, 16), 0);
+/// ```
+#[derive(Debug)]
+pub struct EpochedCounter {
+    epochs: Box<[InvocationCounter]>,
+}
+
+impl EpochedCounter {
+    /// Creates `epoch_count` empty counters, each with `2^slot_count_exp` slots of
+    /// `2^slot_size_exp` time units.
+    pub fn new(epoch_count: usize, slot_count_exp: u8, slot_size_exp: u8) -> Self {
+        let epochs = (0..epoch_count)
+            .map(|_| InvocationCounter::new(slot_count_exp, slot_size_exp))
+            .collect();
+
+        Self { epochs }
+    }
+
+    /// Returns the number of epochs.
+    pub fn epoch_count(&self) -> usize {
+        self.epochs.len()
+    }
+
+    /// Returns the counter of `epoch_id`, or `None` if there is no such epoch.
+    pub fn epoch(&self, epoch_id: usize) -> Option<&InvocationCounter> {
+        self.epochs.get(epoch_id)
+    }
+
+    /// Registers an invocation at the specified time in `epoch_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epoch_id` isn't smaller than [`epoch_count`](Self::epoch_count).
+    pub fn register(&self, epoch_id: usize, current_time: u64) {
+        self.epochs[epoch_id].register(current_time);
+    }
+
+    /// Returns the number of invocations of `epoch_id` within the specified time range, like
+    /// [`InvocationCounter::count_in`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epoch_id` isn't smaller than [`epoch_count`](Self::epoch_count).
+    pub fn count_in(&self, epoch_id: usize, start_time: u64, end_time: u64) -> u32 {
+        self.epochs[epoch_id].count_in(start_time, end_time)
+    }
+
+    /// Drops every invocation registered in `epoch_id`, leaving the other epochs untouched.
+    ///
+    /// The epoch can be reused right away, e.g. for the next experiment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epoch_id` isn't smaller than [`epoch_count`](Self::epoch_count).
+    pub fn drop_epoch(&self, epoch_id: usize) {
+        self.epochs[epoch_id].clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epochs_are_independent() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = EpochedCounter::new(2, 3, 2);
+        assert_eq!(counter.epoch_count(), 2);
+
+        for time in [1, 5, 9, 13] {
+            counter.register(0, time);
+        }
+        for time in [2, 3, 30] {
+            counter.register(1, time);
+        }
+
+        assert_eq!(counter.count_in(0, 0, 32), 4);
+        assert_eq!(counter.count_in(1, 0, 32), 3);
+        assert_eq!(counter.count_in(0, 0, 4), 1);
+        assert_eq!(counter.count_in(1, 0, 4), 2);
+
+        counter.drop_epoch(1);
+        assert_eq!(counter.count_in(1, 0, 32), 0);
+        assert_eq!(counter.count_in(0, 0, 32), 4);
+
+        // The dropped epoch is reusable
+        counter.register(1, 20);
+        assert_eq!(counter.count_in(1, 0, 32), 1);
+        assert_eq!(counter.epoch(1).unwrap().count_in(16, 24), 1);
+        assert!(counter.epoch(2).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unknown_epoch_panics() {
+        EpochedCounter::new(2, 3, 2).register(2, 10);
+    }
+}
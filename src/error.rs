@@ -0,0 +1,129 @@
+use alloc::string::String;
+use core::fmt;
+
+/// The error type returned by every fallible operation of this crate.
+///
+/// The enum is `#[non_exhaustive]`: new variants may be added as new fallible operations are
+/// introduced, so matches on it need a wildcard arm.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CounterError {
+    /// The requested counter configuration is invalid.
+    InvalidConfig {
+        /// Why the configuration was rejected
+        reason: &'static str,
+    },
+    /// A slot counter is saturated and can't record further invocations.
+    Overflow {
+        /// Start of the interval whose slot is saturated
+        interval_start: u64,
+    },
+    /// The operation requires two counters with the same geometry.
+    ///
+    /// Two counters share a geometry when both their `slot_count_exp` and `slot_size_exp`
+    /// match.
+    GeometryMismatch {
+        /// `(slot_count_exp, slot_size_exp)` of the counter the operation was invoked on
+        expected: (u8, u8),
+        /// `(slot_count_exp, slot_size_exp)` of the other counter
+        found: (u8, u8),
+    },
+    /// Serialized counter data couldn't be decoded.
+    Decode {
+        /// Why the data was rejected
+        reason: String,
+    },
+    /// An argument passed to the operation is invalid.
+    InvalidArgument {
+        /// Why the argument was rejected
+        reason: &'static str,
+    },
+    /// The query parameters are invalid.
+    InvalidQuery {
+        /// Why the query was rejected
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for CounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CounterError::InvalidConfig { reason } => {
+                write!(f, "invalid counter configuration: {reason}")
+            }
+            CounterError::Overflow { interval_start } => write!(
+                f,
+                "counter overflow: the slot for the interval starting at {interval_start} is saturated"
+            ),
+            CounterError::GeometryMismatch { expected, found } => write!(
+                f,
+                "counter geometry mismatch: expected (slot_count_exp, slot_size_exp) = {expected:?}, found {found:?}"
+            ),
+            CounterError::Decode { reason } => write!(f, "invalid counter data: {reason}"),
+            CounterError::InvalidArgument { reason } => write!(f, "invalid argument: {reason}"),
+            CounterError::InvalidQuery { reason } => write!(f, "invalid query: {reason}"),
+        }
+    }
+}
+
+impl core::error::Error for CounterError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            CounterError::InvalidConfig {
+                reason: "too many slots"
+            }
+            .to_string(),
+            "invalid counter configuration: too many slots"
+        );
+        assert_eq!(
+            CounterError::Overflow { interval_start: 16 }.to_string(),
+            "counter overflow: the slot for the interval starting at 16 is saturated"
+        );
+        assert_eq!(
+            CounterError::GeometryMismatch {
+                expected: (2, 3),
+                found: (3, 3)
+            }
+            .to_string(),
+            "counter geometry mismatch: expected (slot_count_exp, slot_size_exp) = (2, 3), found (3, 3)"
+        );
+        assert_eq!(
+            CounterError::Decode {
+                reason: "expected 16 bytes, found 3".to_string()
+            }
+            .to_string(),
+            "invalid counter data: expected 16 bytes, found 3"
+        );
+        assert_eq!(
+            CounterError::InvalidArgument {
+                reason: "offset out of range"
+            }
+            .to_string(),
+            "invalid argument: offset out of range"
+        );
+        assert_eq!(
+            CounterError::InvalidQuery {
+                reason: "empty range"
+            }
+            .to_string(),
+            "invalid query: empty range"
+        );
+    }
+
+    #[test]
+    fn test_is_std_error() {
+        fn as_error(err: CounterError) -> Box<dyn std::error::Error + Send + Sync> {
+            Box::new(err)
+        }
+
+        let err = as_error(CounterError::Overflow { interval_start: 0 });
+        assert!(err.source().is_none());
+        assert!(err.downcast_ref::<CounterError>().is_some());
+    }
+}
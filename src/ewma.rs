@@ -0,0 +1,184 @@
+use crate::InvocationCounter;
+
+/// An [`InvocationCounter`] wrapper that weighs intervals with an exponential decay.
+///
+/// A hard sliding window drops an interval all at once when it leaves the window, so a burst
+/// shows up as a plateau followed by a cliff. This wrapper smooths that out: when summing, an
+/// interval `age` intervals older than the one containing the latest observed time contributes
+/// `count × alpha^age`, so older intervals fade out gradually instead.
+///
+/// `alpha` is the decay factor per interval, between `0.0` and `1.0`. It relates to the
+/// half-life `h`, in intervals, after which a count only weighs half as much, by
+/// `alpha = 0.5^(1 / h)`, i.e. `h = ln(0.5) / ln(alpha)`: `alpha = 0.5` halves the weight at
+/// every interval, while `alpha = 0.9` has a half-life of about 6.6 intervals. `1.0` disables
+/// the decay and `0.0` only keeps the latest interval. The window still bounds how far back
+/// data goes, so pick a half-life well below the number of slots.
+///
+/// The wrapped counter is unchanged and still answers exact integer queries like
+/// [`count_in`](InvocationCounter::count_in).
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{EwmaCounter, InvocationCounter};
+/// let counter = EwmaCounter::new(InvocationCounter::new(3, 4), 0.5);
+///
+/// counter.counter().register_n(40, 8); // Interval [32, 48)
+/// counter.counter().register_n(50, 4); // Interval [48, 64), the latest
+///
+/// assert_eq!(counter.count_last(64), 4.0 + 8.0 * 0.5);
+/// ```
+#[derive(Debug)]
+pub struct EwmaCounter {
+    counter: InvocationCounter,
+    alpha: f64,
+}
+
+impl EwmaCounter {
+    /// Wraps `counter`, decaying the weight of its intervals by `alpha` per interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` isn't within `0.0..=1.0`.
+    pub fn new(counter: InvocationCounter, alpha: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&alpha),
+            "alpha must be within 0.0..=1.0, got {alpha}"
+        );
+
+        Self { counter, alpha }
+    }
+
+    /// Returns the wrapped counter, e.g. to register invocations.
+    pub fn counter(&self) -> &InvocationCounter {
+        &self.counter
+    }
+
+    /// Returns the wrapped counter.
+    pub fn into_inner(self) -> InvocationCounter {
+        self.counter
+    }
+
+    /// Returns the decay factor per interval.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the decayed number of invocations within the last `window` time units, ending
+    /// at the latest observed time.
+    ///
+    /// The intervals are the ones [`InvocationCounter::count_last`] sums, each weighted by
+    /// `alpha^age`.
+    pub fn count_last(&self, window: u64) -> f64 {
+        let current_max_time = self
+            .counter
+            .max_current_time
+            .load(self.counter.orderings().count_in.max_time_load);
+        let latest_interval = current_max_time >> self.counter.slot_size_exp;
+        let end_time = current_max_time.saturating_add(1);
+        let start_time = end_time.saturating_sub(window.min(self.counter.saturating_window_size()));
+        if start_time >= end_time {
+            return 0.0;
+        }
+
+        let mut sum = 0.0;
+        self.counter
+            .scan_in(start_time, end_time, |interval_start, count| {
+                let age = latest_interval - (interval_start >> self.counter.slot_size_exp);
+                sum += count as f64 * powi(self.alpha, age);
+            });
+
+        sum
+    }
+
+    /// Returns the smoothed invocation rate, per time unit.
+    ///
+    /// This is the decayed count over the whole window divided by the decayed length of the
+    /// window, so a steady rate of invocations yields that rate whatever `alpha` is. The
+    /// interval containing the latest observed time counts as a whole interval, even if it
+    /// is still in progress.
+    pub fn smoothed_rate(&self) -> f64 {
+        let slot_count = self.counter.slots.len() as u64;
+        let decayed_slots = if self.alpha < 1.0 {
+            (1.0 - powi(self.alpha, slot_count)) / (1.0 - self.alpha)
+        } else {
+            slot_count as f64
+        };
+
+        self.count_last(u64::MAX) / (decayed_slots * self.counter.slot_size() as f64)
+    }
+}
+
+/// Returns `base^exp` by squaring, without relying on `std` float functions.
+fn powi(mut base: f64, mut exp: u64) -> f64 {
+    let mut result = 1.0;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result *= base;
+        }
+        base *= base;
+        exp >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_last_decays() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = EwmaCounter::new(InvocationCounter::new(2, 3), 0.5);
+        counter.counter().register_n(1, 16);
+        counter.counter().register_n(17, 8);
+        counter.counter().register_n(25, 4);
+
+        assert_eq!(counter.count_last(u64::MAX), 4.0 + 8.0 / 2.0 + 16.0 / 8.0);
+        assert_eq!(counter.count_last(1), 4.0);
+        assert_eq!(counter.count_last(8), 4.0 + 8.0 / 2.0);
+        assert_eq!(counter.count_last(0), 0.0);
+        // The wrapped counter is unaffected
+        assert_eq!(counter.counter().count(), 28);
+
+        // No decay, or only the latest interval
+        let flat = EwmaCounter::new(counter.into_inner(), 1.0);
+        assert_eq!(flat.count_last(u64::MAX), 28.0);
+        let latest = EwmaCounter::new(flat.into_inner(), 0.0);
+        assert_eq!(latest.count_last(u64::MAX), 4.0);
+    }
+
+    #[test]
+    fn test_smoothed_rate() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        for alpha in [0.0, 0.3, 0.9, 1.0] {
+            let counter = EwmaCounter::new(InvocationCounter::new(3, 2), alpha);
+            assert_eq!(counter.smoothed_rate(), 0.0);
+
+            // A steady 2 invocations per time unit
+            for time in 0..64 {
+                counter.counter().register_n(time, 2);
+            }
+            assert!(
+                (counter.smoothed_rate() - 2.0).abs() < 1e-9,
+                "alpha {alpha}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "alpha must be within 0.0..=1.0")]
+    fn test_invalid_alpha() {
+        EwmaCounter::new(InvocationCounter::new(2, 3), 1.5);
+    }
+
+    #[test]
+    fn test_powi() {
+        assert_eq!(powi(0.5, 0), 1.0);
+        assert_eq!(powi(0.5, 3), 0.125);
+        assert_eq!(powi(0.0, 0), 1.0);
+        assert_eq!(powi(0.0, 5), 0.0);
+        assert_eq!(powi(0.9, 1_000_000), 0.0);
+    }
+}
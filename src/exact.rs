@@ -0,0 +1,288 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::{check_geometry, ring_range, valid_range, window_size, CounterError};
+
+/// The timestamps registered in one interval.
+#[derive(Debug)]
+struct ExactSlot {
+    /// Start of the interval tracked by this slot, or [`EMPTY`](Self::EMPTY)
+    interval_start: u64,
+    timestamps: Vec<u64>,
+}
+
+impl ExactSlot {
+    /// `interval_start` of a slot that never tracked any interval, which no window contains.
+    const EMPTY: u64 = u64::MAX;
+}
+
+/// A sliding window counter that keeps every registered timestamp, so range queries are exact.
+///
+/// It has the same geometry and window as an [`InvocationCounter`](crate::InvocationCounter)
+/// with the same exponents, but instead of a single count per interval each slot stores the
+/// timestamps registered in it. [`count_in`](Self::count_in) then counts exactly the
+/// invocations in `[start_time, end_time)`, instead of rounding both bounds to slot
+/// boundaries.
+///
+/// The price is memory and query time: every invocation retained in the window takes 8 bytes,
+/// and a query costs `O(events)` in the intervals it touches rather than `O(slots)`, plus a
+/// lock per interval. Registrations into the same interval also contend on a mutex. Prefer it
+/// for small windows with moderate traffic, where accuracy at the edges matters.
+///
+/// Only available with the `std` feature.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{ExactCounter, InvocationCounter};
+/// let exact = ExactCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+/// let approximate = InvocationCounter::new(3, 4);
+///
+/// for time in [3, 10, 25] {
+///     exact.register(time);
+///     approximate.register(time);
+/// }
+///
+/// assert_eq!(exact.count_in(5, 20), 1);       // Only 10
+/// assert_eq!(approximate.count_in(5, 20), 3); // Aligned to [0, 32)
+/// ```
+#[derive(Debug)]
+pub struct ExactCounter {
+    slots: Box<[Mutex<ExactSlot>]>,
+    slot_count_exp: u8,
+    slot_size_exp: u8,
+    max_current_time: AtomicU64,
+}
+
+impl ExactCounter {
+    /// Creates a new `ExactCounter` with `2^slot_count_exp` slots of `2^slot_size_exp` time
+    /// units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected by [`try_new`](Self::try_new).
+    pub fn new(slot_count_exp: u8, slot_size_exp: u8) -> Self {
+        Self::try_new(slot_count_exp, slot_size_exp).unwrap_or_else(|error| {
+            panic!("ExactCounter::new({slot_count_exp}, {slot_size_exp}): {error}")
+        })
+    }
+
+    /// Creates a new `ExactCounter`, rejecting the same configurations as
+    /// [`InvocationCounter::try_new`](crate::InvocationCounter::try_new).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidConfig`] if either exponent is out of range.
+    pub fn try_new(slot_count_exp: u8, slot_size_exp: u8) -> Result<Self, CounterError> {
+        check_geometry(slot_count_exp, slot_size_exp)?;
+
+        let slots = (0..1usize << slot_count_exp)
+            .map(|_| {
+                Mutex::new(ExactSlot {
+                    interval_start: ExactSlot::EMPTY,
+                    timestamps: Vec::new(),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            slots,
+            slot_count_exp,
+            slot_size_exp,
+            max_current_time: AtomicU64::new(0),
+        })
+    }
+
+    /// Registers an invocation at the specified time.
+    ///
+    /// Like [`InvocationCounter::register`](crate::InvocationCounter::register), a timestamp
+    /// whose interval already left the window ending at the latest observed time is dropped.
+    pub fn register(&self, current_time: u64) {
+        let interval_start = current_time >> self.slot_size_exp << self.slot_size_exp;
+        let window_start = ring_range(
+            self.max_current_time.load(Ordering::Acquire),
+            self.slot_size_exp,
+            window_size(self.slot_count_exp, self.slot_size_exp),
+        )
+        .start;
+        if interval_start < window_start {
+            return;
+        }
+
+        {
+            let mut slot = self
+                .slot(interval_start)
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if slot.interval_start != interval_start {
+                // A concurrent registration may have already moved the slot on to a newer
+                // interval, which evicted this one
+                if slot.interval_start != ExactSlot::EMPTY && slot.interval_start > interval_start {
+                    return;
+                }
+                slot.interval_start = interval_start;
+                slot.timestamps.clear();
+            }
+            slot.timestamps.push(current_time);
+        }
+
+        self.max_current_time
+            .fetch_max(current_time, Ordering::AcqRel);
+    }
+
+    /// Returns the number of invocations registered in `[start_time, end_time)`.
+    ///
+    /// Unlike [`InvocationCounter::count_in`](crate::InvocationCounter::count_in), the bounds
+    /// aren't aligned to slot boundaries: every retained timestamp is compared against them.
+    /// Only invocations within the current window are counted, and the result saturates at
+    /// `u32::MAX`.
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let valid_range = self.valid_range(start_time, end_time);
+        if valid_range.is_empty() {
+            return 0;
+        }
+
+        let first_interval = valid_range.start >> self.slot_size_exp;
+        let last_interval = (valid_range.end - 1) >> self.slot_size_exp;
+        let mut count = 0u32;
+        for interval in first_interval..=last_interval {
+            let interval_start = interval << self.slot_size_exp;
+            let slot = self
+                .slot(interval_start)
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if slot.interval_start == interval_start {
+                let in_range = slot
+                    .timestamps
+                    .iter()
+                    .filter(|&&time| start_time <= time && time < end_time)
+                    .count();
+                count = count.saturating_add(u32::try_from(in_range).unwrap_or(u32::MAX));
+            }
+        }
+
+        count
+    }
+
+    /// Returns the total number of invocations within the sliding window ending at the latest
+    /// observed time.
+    pub fn count(&self) -> u32 {
+        // Sum the slots directly: the exclusive end of `count_in(0, u64::MAX)` would miss a
+        // registration at `u64::MAX`
+        let window_start = ring_range(
+            self.max_current_time.load(Ordering::Acquire),
+            self.slot_size_exp,
+            window_size(self.slot_count_exp, self.slot_size_exp),
+        )
+        .start;
+
+        let mut count = 0u32;
+        for slot in self.slots.iter() {
+            let slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+            // Empty slots hold no timestamps, so they add nothing whatever their interval
+            if slot.interval_start >= window_start {
+                let len = u32::try_from(slot.timestamps.len()).unwrap_or(u32::MAX);
+                count = count.saturating_add(len);
+            }
+        }
+
+        count
+    }
+
+    fn slot(&self, interval_start: u64) -> &Mutex<ExactSlot> {
+        let slot_count_mask = (1u64 << self.slot_count_exp) - 1;
+        &self.slots[((interval_start >> self.slot_size_exp) & slot_count_mask) as usize]
+    }
+
+    fn valid_range(&self, start_time: u64, end_time: u64) -> std::ops::Range<u64> {
+        valid_range(
+            self.max_current_time.load(Ordering::Acquire),
+            self.slot_size_exp,
+            window_size(self.slot_count_exp, self.slot_size_exp),
+            start_time,
+            end_time,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InvocationCounter;
+
+    #[test]
+    fn test_exact_count_in() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let exact = ExactCounter::new(2, 3);
+        let times = [1, 3, 9, 12, 15, 17, 25, 30];
+        for time in times {
+            exact.register(time);
+        }
+
+        for start in 0..40 {
+            for end in start..40 {
+                let expected = times
+                    .iter()
+                    .filter(|&&time| start <= time && time < end)
+                    .count();
+                assert_eq!(exact.count_in(start, end) as usize, expected);
+            }
+        }
+        assert_eq!(exact.count(), 8);
+    }
+
+    #[test]
+    fn test_exact_window() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let exact = ExactCounter::new(2, 3);
+        let approximate = InvocationCounter::new(2, 3);
+        for time in [1, 3, 9, 17, 25, 33, 34, 2, 50, 41, 20] {
+            exact.register(time);
+            approximate.register(time);
+        }
+
+        // On slot boundaries, both counters agree
+        for start in (0..72).step_by(8) {
+            for end in (start..72).step_by(8) {
+                assert_eq!(
+                    exact.count_in(start, end),
+                    approximate.count_in(start, end),
+                    "[{start}, {end})"
+                );
+            }
+        }
+        assert_eq!(exact.count_in(40, 42), 1);
+        assert_eq!(exact.count_in(42, 50), 0);
+
+        // [0, 8) left the window: a stale registration doesn't clobber [32, 40)
+        exact.register(4);
+        assert_eq!(exact.count_in(32, 40), 2);
+        assert_eq!(exact.count_in(0, 8), 0);
+        assert_eq!(exact.count(), approximate.count());
+    }
+
+    #[test]
+    fn test_exact_count_up_to_u64_max() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let exact = ExactCounter::new(2, 3);
+        exact.register(u64::MAX - 20);
+        exact.register(u64::MAX);
+        assert_eq!(exact.count(), 2);
+
+        // Single-unit slots: the last interval starts at `u64::MAX` itself
+        let exact = ExactCounter::new(2, 0);
+        exact.register(u64::MAX - 1);
+        exact.register(u64::MAX);
+        assert_eq!(exact.count(), 2);
+    }
+
+    #[test]
+    fn test_exact_invalid_config() {
+        assert!(ExactCounter::try_new(InvocationCounter::MAX_SLOT_COUNT_EXP + 1, 0).is_err());
+        assert!(ExactCounter::try_new(2, 64).is_err());
+    }
+}
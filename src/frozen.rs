@@ -0,0 +1,130 @@
+use alloc::boxed::Box;
+
+use crate::{aligned_range, InvocationCounter};
+
+impl InvocationCounter {
+    /// Consumes this counter and converts its current window into an immutable
+    /// [`FrozenCounter`].
+    ///
+    /// Only the non-empty slots of the current window are kept, sorted by interval, so the
+    /// result is usually much smaller than the ring buffer and answers the same queries
+    /// without any atomic operation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(10);
+    /// counter.register(40);
+    ///
+    /// let frozen = counter.freeze();
+    /// assert_eq!(frozen.count_in(0, 32), 1);
+    /// assert_eq!(frozen.count(), 2);
+    /// ```
+    pub fn freeze(self) -> FrozenCounter {
+        FrozenCounter {
+            slot_size_exp: self.slot_size_exp,
+            intervals: self.snapshot().into_boxed_slice(),
+        }
+    }
+}
+
+/// An immutable, compact archive of an [`InvocationCounter`] window, created by
+/// [`InvocationCounter::freeze`].
+///
+/// The non-empty intervals are stored sorted by start time, so a range query locates its
+/// bounds with a binary search in `O(log n)` and only sums the intervals it covers. Queries
+/// answer exactly what the live counter answered at freeze time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrozenCounter {
+    slot_size_exp: u8,
+    /// `(interval_start, counter)` for every non-empty interval, sorted by `interval_start`
+    intervals: Box<[(u64, u32)]>,
+}
+
+impl FrozenCounter {
+    /// Returns the slot size exponent of the frozen counter.
+    pub fn slot_size_exp(&self) -> u8 {
+        self.slot_size_exp
+    }
+
+    /// Returns the `(interval_start, count)` pairs of every non-empty interval, oldest first.
+    pub fn intervals(&self) -> &[(u64, u32)] {
+        &self.intervals
+    }
+
+    /// Returns the total number of invocations within the specified time range.
+    ///
+    /// Follows the same rules as [`InvocationCounter::count_in`]: `start_time` is inclusive,
+    /// `end_time` is exclusive, both are aligned to slot boundaries, and the result saturates
+    /// at `u32::MAX`.
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let range = aligned_range(self.slot_size_exp, start_time, end_time);
+        let first = self
+            .intervals
+            .partition_point(|&(interval_start, _)| interval_start < range.start);
+        let last = self
+            .intervals
+            .partition_point(|&(interval_start, _)| interval_start < range.end);
+
+        self.intervals[first..last]
+            .iter()
+            .fold(0, |sum, &(_, count)| sum.saturating_add(count))
+    }
+
+    /// Returns the total number of invocations in the frozen window, saturating at `u32::MAX`.
+    pub fn count(&self) -> u32 {
+        self.intervals
+            .iter()
+            .fold(0, |sum, &(_, count)| sum.saturating_add(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frozen_matches_counter() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for t in [1, 3, 9, 17, 25, 30, 33, 34, 41, 50] {
+            counter.register(t);
+        }
+
+        let expected: Vec<_> = (0..64)
+            .flat_map(|start| (start..64).map(move |end| (start, end)))
+            .map(|(start, end)| counter.count_in(start, end))
+            .collect();
+        let window_count = counter.count_in(0, 64);
+
+        let frozen = counter.freeze();
+        let actual: Vec<_> = (0..64)
+            .flat_map(|start| (start..64).map(move |end| (start, end)))
+            .map(|(start, end)| frozen.count_in(start, end))
+            .collect();
+        assert_eq!(actual, expected);
+        assert_eq!(frozen.count(), window_count);
+        assert_eq!(frozen.slot_size_exp(), 2);
+
+        // Only the non-empty intervals of the window [20, 52) are kept, sorted
+        assert_eq!(
+            frozen.intervals(),
+            &[(24, 1), (28, 1), (32, 2), (40, 1), (48, 1)]
+        );
+    }
+
+    #[test]
+    fn test_freeze_empty_counter() {
+        let frozen = InvocationCounter::new(3, 2).freeze();
+        assert_eq!(frozen.intervals(), &[]);
+        assert_eq!(frozen.count(), 0);
+        assert_eq!(frozen.count_in(0, 64), 0);
+    }
+}
@@ -0,0 +1,93 @@
+use hdrhistogram::Histogram;
+
+use crate::InvocationCounter;
+
+impl InvocationCounter {
+    /// Exports the distribution of per-interval invocation counts into an HDR histogram.
+    ///
+    /// Every interval of the current window contributes one sample: the number of invocations
+    /// registered in it, or `0` if it has no data. The resulting histogram answers questions
+    /// like "what is the p99 number of invocations per interval?" and can be handed to the
+    /// wider `hdrhistogram` ecosystem for percentile analysis.
+    ///
+    /// The histogram tracks values with 3 significant digits.
+    ///
+    /// Only available with the `hdrhistogram` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(2, 4); // 4 slots × 16 units = 64-unit window
+    ///
+    /// counter.register(0);
+    /// counter.register(16);
+    /// counter.register(17);
+    ///
+    /// let histogram = counter.to_hdr();
+    /// assert_eq!(histogram.len(), 4); // One sample per interval in the window
+    /// assert_eq!(histogram.max(), 2);
+    /// assert_eq!(histogram.min(), 0);
+    /// ```
+    pub fn to_hdr(&self) -> Histogram<u64> {
+        let mut histogram =
+            Histogram::<u64>::new(3).expect("3 significant digits is a valid precision");
+
+        for slot in &self.slots {
+            let (time_in_slot, count) = slot.load(&self.orderings().count_in);
+            let count = if self.is_in_window(time_in_slot) {
+                count
+            } else {
+                0
+            };
+            histogram
+                .record(count as u64)
+                .expect("histogram auto-resizes");
+        }
+
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InvocationCounter;
+
+    #[test]
+    fn test_to_hdr_percentiles() {
+        // 128 slots (2^7) * 2 time units (2^1) = 256 time units window
+        let counter = InvocationCounter::new(7, 1);
+
+        // Interval i (0..100) gets i + 1 invocations; the other 28 intervals stay empty
+        for i in 0..100u64 {
+            for _ in 0..=i {
+                counter.register(i * 2);
+            }
+        }
+
+        let histogram = counter.to_hdr();
+        assert_eq!(histogram.len(), 128);
+        assert_eq!(histogram.min(), 0);
+        assert_eq!(histogram.max(), 100);
+        // 28 zeros followed by 1..=100
+        assert_eq!(histogram.count_at(0), 28);
+        assert_eq!(histogram.value_at_quantile(0.5), 36);
+        assert_eq!(histogram.value_at_quantile(0.9), 88);
+        assert_eq!(histogram.value_at_quantile(1.0), 100);
+    }
+
+    #[test]
+    fn test_to_hdr_excludes_evicted_intervals() {
+        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
+        let counter = InvocationCounter::new(1, 2);
+
+        counter.register(0);
+        counter.register(0);
+        counter.register(100);
+
+        let histogram = counter.to_hdr();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram.max(), 1);
+        assert_eq!(histogram.count_at(0), 1);
+    }
+}
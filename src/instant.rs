@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use crate::InvocationCounter;
+
+/// An [`InvocationCounter`] wrapper that takes its timestamps from [`Instant::now`].
+///
+/// The wrapper records the `Instant` it was created at and registers every invocation at the
+/// number of **milliseconds** elapsed since then, so callers never handle raw `u64`
+/// timestamps. The wrapped counter's geometry is therefore interpreted in milliseconds:
+/// [`InvocationCounter::from_window`] builds one from durations.
+///
+/// Millisecond timestamps overflow a `u64` after about 584 million years; past that, they
+/// saturate at `u64::MAX`.
+///
+/// Only available with the `std` feature.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{InstantCounter, InvocationCounter};
+/// # use std::time::Duration;
+/// let counter = InvocationCounter::from_window(Duration::from_secs(60), Duration::from_secs(1));
+/// let counter = InstantCounter::new(counter.unwrap());
+///
+/// counter.record_now();
+/// counter.record_now();
+///
+/// assert_eq!(counter.count_last(Duration::from_secs(10)), 2);
+/// ```
+#[derive(Debug)]
+pub struct InstantCounter {
+    counter: InvocationCounter,
+    base: Instant,
+}
+
+impl InstantCounter {
+    /// Wraps `counter`, measuring time in milliseconds from now on.
+    pub fn new(counter: InvocationCounter) -> Self {
+        Self {
+            counter,
+            base: Instant::now(),
+        }
+    }
+
+    /// Returns the wrapped counter, e.g. to query ranges of milliseconds since
+    /// [`base`](Self::base).
+    pub fn counter(&self) -> &InvocationCounter {
+        &self.counter
+    }
+
+    /// Returns the wrapped counter.
+    pub fn into_inner(self) -> InvocationCounter {
+        self.counter
+    }
+
+    /// Returns the instant that timestamp `0` of the wrapped counter corresponds to.
+    pub fn base(&self) -> Instant {
+        self.base
+    }
+
+    /// Registers an invocation now.
+    pub fn record_now(&self) {
+        self.counter.register(self.millis_at(Instant::now()));
+    }
+
+    /// Returns the total number of invocations within the last `window`, ending now.
+    ///
+    /// `window` is rounded up to whole milliseconds, then the query follows the same rules as
+    /// [`InvocationCounter::count_last`]: it covers the current millisecond and is aligned to
+    /// slot boundaries.
+    pub fn count_last(&self, window: Duration) -> u32 {
+        self.count_last_at(window, Instant::now())
+    }
+
+    fn count_last_at(&self, window: Duration, now: Instant) -> u32 {
+        let window = u64::try_from(window.as_nanos().div_ceil(1_000_000)).unwrap_or(u64::MAX);
+        let end_time = self.millis_at(now).saturating_add(1);
+
+        self.counter
+            .count_in(end_time.saturating_sub(window), end_time)
+    }
+
+    /// Returns the timestamp of `instant`: the milliseconds elapsed since the base, saturating
+    /// at `u64::MAX`.
+    fn millis_at(&self, instant: Instant) -> u64 {
+        let elapsed = instant.saturating_duration_since(self.base);
+        u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instant_counter() {
+        // 4 slots (2^2) * 8 milliseconds (2^3) = 32 milliseconds window
+        let counter = InstantCounter::new(InvocationCounter::new(2, 3));
+        let at = |millis| counter.base() + Duration::from_millis(millis);
+
+        assert_eq!(counter.millis_at(at(0)), 0);
+        assert_eq!(counter.millis_at(at(20) + Duration::from_micros(999)), 20);
+        assert_eq!(
+            counter.millis_at(counter.base() - Duration::from_millis(1)),
+            0
+        );
+
+        for millis in [3, 9, 20] {
+            counter.counter().register(counter.millis_at(at(millis)));
+        }
+        assert_eq!(counter.count_last_at(Duration::from_millis(1), at(21)), 1);
+        assert_eq!(counter.count_last_at(Duration::from_micros(100), at(21)), 1);
+        assert_eq!(counter.count_last_at(Duration::from_millis(10), at(21)), 2);
+        assert_eq!(counter.count_last_at(Duration::MAX, at(21)), 3);
+        assert_eq!(counter.count_last_at(Duration::ZERO, at(21)), 0);
+        // Nothing was recorded recently
+        assert_eq!(counter.count_last_at(Duration::from_millis(10), at(100)), 0);
+
+        assert_eq!(counter.into_inner().lifetime_count(), 3);
+    }
+
+    #[test]
+    fn test_record_now() {
+        let counter = InstantCounter::new(InvocationCounter::new(4, 10));
+        counter.record_now();
+        counter.record_now();
+
+        assert_eq!(counter.count_last(Duration::from_secs(1)), 2);
+        assert_eq!(counter.counter().lifetime_count(), 2);
+    }
+}
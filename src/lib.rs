@@ -1,22 +1,423 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+extern crate alloc;
 
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hint::spin_loop;
+use core::ops::{Bound, Range, RangeBounds};
+use core::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+mod bytes;
+#[cfg(feature = "std")]
+mod cached;
+mod chain;
+mod epoched;
+mod error;
+mod ewma;
+#[cfg(feature = "std")]
+mod exact;
+mod frozen;
+#[cfg(feature = "hdrhistogram")]
+mod hdr;
+#[cfg(feature = "std")]
+mod instant;
+mod multi;
+mod orderings;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "metrics")]
+mod prometheus;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "simd")]
+mod simd;
+mod snapshot;
+mod static_counter;
+#[cfg(feature = "tokio")]
+mod ticker;
+
+#[cfg(feature = "std")]
+pub use cached::CachedCounter;
+pub use chain::CounterChain;
+pub use epoched::EpochedCounter;
+pub use error::CounterError;
+pub use ewma::EwmaCounter;
+#[cfg(feature = "std")]
+pub use exact::ExactCounter;
+pub use frozen::FrozenCounter;
+#[cfg(feature = "std")]
+pub use instant::InstantCounter;
+pub use multi::MultiCounter;
+pub use orderings::{
+    CountInOrderings, OrderingProfile, OrderingReport, RegisterOrderings, ORDERINGS,
+};
+pub use snapshot::CounterSnapshot;
+pub use static_counter::StaticInvocationCounter;
+#[cfg(feature = "tokio")]
+pub use ticker::spawn_ticker;
+
+/// A slot of the ring buffer.
+///
+/// Slots are aligned to 64 bytes, the cache line size of common CPUs, so that each one owns
+/// its cache line: threads registering into neighbouring intervals don't invalidate each
+/// other's lines (false sharing). The tradeoff is memory, 64 bytes per slot instead of 16.
+///
+/// `interval_start` and `counter` are separate atomics, so replacing a slot's content (a
+/// rollover, a merge, a clear) can't update both at once. The replacements are serialized by
+/// a seqlock on `version`, which readers validate in [`load`](Self::load): a reader never pairs
+/// an interval with the count of the interval it replaced. Plain increments don't take the
+/// lock, they only touch `counter`.
 #[derive(Debug)]
+#[repr(align(64))]
 struct Slot {
+    /// Start of the interval tracked by this slot, or [`EMPTY`](Self::EMPTY)
     interval_start: AtomicU64,
     counter: AtomicU32,
+    /// Seqlock version: odd while a thread replaces the slot's content, bumped by two by
+    /// every replacement
+    version: AtomicU32,
 }
 
 impl Slot {
+    /// `interval_start` of a slot that never tracked any interval.
+    ///
+    /// No window ever contains it, since window ends are exclusive and saturate at
+    /// `u64::MAX`, so empty slots are never counted. Using `0` instead would make an empty
+    /// slot indistinguishable from one tracking the interval that starts at time `0`.
+    const EMPTY: u64 = u64::MAX;
+
     fn new() -> Self {
+        Self::with_content(Self::EMPTY, 0)
+    }
+
+    fn with_content(interval_start: u64, count: u32) -> Self {
         Self {
-            interval_start: AtomicU64::new(0),
-            counter: AtomicU32::new(0),
+            interval_start: AtomicU64::new(interval_start),
+            counter: AtomicU32::new(count),
+            version: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the slot's `(interval_start, counter)`, read as a consistent pair.
+    ///
+    /// Retries until no replacement overlapped the two loads, i.e. until it observes the same
+    /// even version before and after them. The count may still be one that increments are
+    /// racing to raise, but it always belongs to the returned interval.
+    fn load(&self, orderings: &CountInOrderings) -> (u64, u32) {
+        loop {
+            let version = self.version.load(orderings.slot_version_load);
+            if version & 1 == 0 {
+                let interval_start = self.interval_start.load(orderings.slot_interval_load);
+                let count = self.counter.load(orderings.slot_counter_load);
+                // Orders the loads above before the version check below
+                fence(Ordering::Acquire);
+                if self.version.load(Ordering::Relaxed) == version {
+                    return (interval_start, count);
+                }
+            }
+            spin_loop();
+        }
+    }
+
+    /// Calls `f` with the slot's `interval_start` while holding the seqlock, so that no other
+    /// thread can replace the slot's content until `f` returns.
+    fn locked<R>(&self, orderings: &RegisterOrderings, f: impl FnOnce(u64) -> R) -> R {
+        let mut version = self.version.load(Ordering::Relaxed);
+        loop {
+            if version & 1 == 0 {
+                match self.version.compare_exchange_weak(
+                    version,
+                    version.wrapping_add(1),
+                    orderings.slot_rollover_success,
+                    orderings.slot_rollover_failure,
+                ) {
+                    Ok(_) => break,
+                    Err(actual) => version = actual,
+                }
+            } else {
+                spin_loop();
+                version = self.version.load(Ordering::Relaxed);
+            }
+        }
+        // Readers that observe any write below also observe the odd version
+        fence(Ordering::Release);
+
+        let result = f(self.interval_start.load(Ordering::Relaxed));
+
+        self.version
+            .store(version.wrapping_add(2), orderings.slot_unlock);
+        result
+    }
+
+    /// Replaces the slot's content with `replace(interval_start)`, unless it returns `None`.
+    ///
+    /// `replace` runs while holding the seqlock, so it sees the latest `interval_start` and no
+    /// other replacement can happen until the new content is written. Returns the replaced
+    /// `(interval_start, counter)`, or `None` if the content was kept.
+    fn replace(
+        &self,
+        orderings: &RegisterOrderings,
+        replace: impl FnOnce(u64) -> Option<(u64, u32)>,
+    ) -> Option<(u64, u32)> {
+        self.locked(orderings, |time_in_slot| {
+            replace(time_in_slot).map(|(interval_start, count)| {
+                self.interval_start.store(interval_start, Ordering::Relaxed);
+                (time_in_slot, self.counter.swap(count, orderings.slot_reset))
+            })
+        })
+    }
+
+    /// Unconditionally replaces the slot's content, returning the replaced one.
+    fn swap(&self, orderings: &RegisterOrderings, interval_start: u64, count: u32) -> (u64, u32) {
+        self.replace(orderings, |_| Some((interval_start, count)))
+            .expect("an unconditional replacement always happens")
+    }
+
+    /// Adds `n` invocations to this slot for the interval starting at `interval_start`.
+    ///
+    /// If the slot tracks a different interval, it is rolled over under the seqlock, so
+    /// exactly one racing thread resets it. The counter saturates at `u32::MAX` instead of
+    /// wrapping.
+    ///
+    /// An increment that checked the interval just before another thread rolled the slot over
+    /// can still land in the new interval: the lock only guarantees that readers see
+    /// consistent pairs, not that every increment is attributed to its own interval.
+    ///
+    /// Returns the number of invocations evicted by the rollover (`None` for the threads that
+    /// didn't perform it) and the number of invocations that didn't fit in the counter.
+    fn register(
+        &self,
+        orderings: &RegisterOrderings,
+        interval_start: u64,
+        n: u32,
+    ) -> (Option<u32>, u32) {
+        loop {
+            if self.interval_start.load(orderings.slot_interval_load) == interval_start {
+                let previous = self
+                    .counter
+                    .fetch_update(
+                        orderings.slot_increment,
+                        orderings.slot_increment,
+                        |count| Some(count.saturating_add(n)),
+                    )
+                    .unwrap_or_else(|count| count);
+                let overflow = n - (u32::MAX - previous).min(n);
+                return (None, overflow);
+            }
+            // This thread rolls the slot over, unless another one did it first: then retry
+            // the increment against the new interval
+            let rollover =
+                |time_in_slot| (time_in_slot != interval_start).then_some((interval_start, n));
+            if let Some((_, evicted)) = self.replace(orderings, rollover) {
+                return (Some(evicted), 0);
+            }
+        }
+    }
+
+    /// Raises this slot's counter to `count` for the interval starting at `interval_start`,
+    /// leaving it unchanged if it is already higher.
+    ///
+    /// Rolls the slot over exactly like [`register`](Self::register). Returns the number of
+    /// invocations evicted by the rollover (`None` for the threads that didn't perform it) and
+    /// how much the counter was raised by.
+    fn register_max(
+        &self,
+        orderings: &RegisterOrderings,
+        interval_start: u64,
+        count: u32,
+    ) -> (Option<u32>, u32) {
+        loop {
+            if self.interval_start.load(orderings.slot_interval_load) == interval_start {
+                let previous = self.counter.fetch_max(count, orderings.slot_increment);
+                return (None, count.saturating_sub(previous));
+            }
+            let rollover =
+                |time_in_slot| (time_in_slot != interval_start).then_some((interval_start, count));
+            if let Some((_, evicted)) = self.replace(orderings, rollover) {
+                return (Some(evicted), count);
+            }
+        }
+    }
+
+    /// Removes up to `n` invocations from this slot if it tracks the interval starting at
+    /// `interval_start`, saturating at `0`.
+    ///
+    /// The check and the decrement happen under the seqlock, so they can't land in an interval
+    /// that replaced this one in between. Returns the number of invocations removed.
+    fn unregister(&self, orderings: &RegisterOrderings, interval_start: u64, n: u32) -> u32 {
+        if self.interval_start.load(orderings.slot_interval_load) != interval_start {
+            return 0;
+        }
+        self.locked(orderings, |time_in_slot| {
+            if time_in_slot != interval_start {
+                return 0;
+            }
+            let previous = self
+                .counter
+                .fetch_update(
+                    orderings.slot_increment,
+                    orderings.slot_increment,
+                    |count| Some(count.saturating_sub(n)),
+                )
+                .unwrap_or_else(|count| count);
+            previous.min(n)
+        })
+    }
+}
+
+impl Slot {
+    /// Folds `count` invocations of the interval starting at `interval_start` into this slot.
+    ///
+    /// The invocations are added if the slot tracks the same interval, and replace the slot's
+    /// content if it tracks an older interval or is empty. They are dropped if the slot tracks
+    /// a newer interval, which already evicted them. Returns the number of invocations that
+    /// didn't fit in the counter.
+    fn merge(&self, orderings: &RegisterOrderings, interval_start: u64, count: u32) -> u32 {
+        let is_older = |time_in_slot| time_in_slot == Self::EMPTY || time_in_slot < interval_start;
+        loop {
+            let time_in_slot = self.interval_start.load(orderings.slot_interval_load);
+            if time_in_slot == interval_start {
+                return self.register(orderings, interval_start, count).1;
+            }
+            if !is_older(time_in_slot) {
+                return 0;
+            }
+            let replacement =
+                |time_in_slot| is_older(time_in_slot).then_some((interval_start, count));
+            if self.replace(orderings, replacement).is_some() {
+                return 0;
+            }
         }
     }
 }
 
+/// How a registration updates its slot.
+#[derive(Debug, Clone, Copy)]
+enum SlotUpdate {
+    /// Adds invocations to the slot
+    Add(u32),
+    /// Raises the slot to an absolute count
+    Max(u32),
+}
+
+/// Returns the sliding window size in time units: `2^slot_count_exp × 2^slot_size_exp`.
+///
+/// If `slot_count_exp + slot_size_exp >= 64` the window doesn't fit in a `u64`; `u64::MAX` is
+/// returned instead, so the window covers the whole past.
+fn window_size(slot_count_exp: u8, slot_size_exp: u8) -> u64 {
+    1u64.checked_shl(slot_size_exp as u32 + slot_count_exp as u32)
+        .unwrap_or(u64::MAX)
+}
+
+/// Returns the range of `interval_start` values currently held by the ring buffer: the
+/// `window_size` time units ending with the slot containing `current_max_time`.
+fn ring_range(current_max_time: u64, slot_size_exp: u8, window_size: u64) -> Range<u64> {
+    // Saturate rather than wrap when `current_max_time` is in the last slot of the `u64` range
+    let ring_end =
+        (current_max_time >> slot_size_exp << slot_size_exp).saturating_add(1 << slot_size_exp);
+    let ring_start = ring_end.saturating_sub(window_size);
+    ring_start..ring_end
+}
+
+/// Returns `[start_time, end_time)` widened to slot boundaries.
+fn aligned_range(slot_size_exp: u8, start_time: u64, end_time: u64) -> Range<u64> {
+    // start_time is inclusive: include the slot that contains start_time
+    let asked_start = start_time >> slot_size_exp << slot_size_exp;
+    // end_time is exclusive: find the slot that would contain end_time and use its start as boundary
+    // If end_time is exactly at a slot boundary, use that boundary
+    // Otherwise, use the start of the next slot after the slot containing end_time
+    // With single-unit slots (slot_size_exp == 0) the mask is 0: every time is a boundary
+    let asked_end = if end_time & ((1 << slot_size_exp) - 1) == 0 {
+        // end_time is exactly at slot boundary
+        end_time
+    } else {
+        // end_time is within a slot, use start of next slot, saturating when end_time is in
+        // the last slot of the u64 range
+        (end_time >> slot_size_exp << slot_size_exp).saturating_add(1 << slot_size_exp)
+    };
+    asked_start..asked_end
+}
+
+/// Returns [`CounterError::InvalidConfig`] unless a counter with these exponents can be
+/// built, as documented on [`InvocationCounter::try_new`].
+fn check_geometry(slot_count_exp: u8, slot_size_exp: u8) -> Result<(), CounterError> {
+    if slot_count_exp > InvocationCounter::MAX_SLOT_COUNT_EXP {
+        return Err(CounterError::InvalidConfig {
+            reason: "slot_count_exp must be at most MAX_SLOT_COUNT_EXP",
+        });
+    }
+    if slot_size_exp >= 64 {
+        return Err(CounterError::InvalidConfig {
+            reason: "slot_size_exp must be below 64",
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the range of `interval_start` values that a query over `[start_time, end_time)`
+/// should sum.
+///
+/// This is the intersection of the ring buffer's valid range (the `window_size` time units
+/// ending with the slot containing `current_max_time`) and the requested range aligned to slot
+/// boundaries.
+fn valid_range(
+    current_max_time: u64,
+    slot_size_exp: u8,
+    window_size: u64,
+    start_time: u64,
+    end_time: u64,
+) -> Range<u64> {
+    let ring_buffer_range = ring_range(current_max_time, slot_size_exp, window_size);
+    let asked_range = aligned_range(slot_size_exp, start_time, end_time);
+
+    // Find the intersection of ring buffer range and requested range
+    ring_buffer_range.start.max(asked_range.start)..ring_buffer_range.end.min(asked_range.end)
+}
+
+/// The result of a registration, as returned by [`InvocationCounter::register_n_observed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterOutcome {
+    /// Number of invocations registered, as counted by the
+    /// [lifetime total](InvocationCounter::lifetime_count). When the slot's counter saturates,
+    /// this still includes the invocations that didn't fit, which go to the
+    /// [spillover](InvocationCounter::spillover) instead of the slot
+    pub added: u32,
+    /// Number of invocations evicted because the slot was reused for a newer interval
+    pub evicted: u32,
+}
+
+/// How a range query rounds bounds that fall inside a slot, as used by
+/// [`InvocationCounter::count_in_rounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundMode {
+    /// Count every slot that overlaps the range, rounding the start down and the end up to
+    /// slot boundaries. This may overcount, and is what [`InvocationCounter::count_in`] does.
+    Outward,
+    /// Only count slots fully contained in the range, rounding the start up and the end down
+    /// to slot boundaries. This may undercount.
+    Inward,
+}
+
+/// One interval of a counter's window, as returned by [`InvocationCounter::to_buckets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    /// Start of the interval (inclusive)
+    pub start: u64,
+    /// End of the interval (exclusive): `start` plus the slot size, saturating at `u64::MAX`
+    pub end: u64,
+    /// Number of invocations registered in the interval
+    pub count: u32,
+}
+
 /// A structure for tracking invocation counts over sliding time windows.
 ///
 /// `InvocationCounter` implements a ring buffer-based algorithm that efficiently answers the question:
@@ -52,6 +453,30 @@ impl Slot {
 /// As time progresses, slots are reused in a circular fashion. When a new time interval begins
 /// that maps to an already-occupied slot, the slot is reset and begins tracking the new interval.
 ///
+/// # Thread Safety
+///
+/// `InvocationCounter` is [`Send`] and [`Sync`]: share it between threads behind an `Arc` or
+/// a `static`. Every method taking `&self` is safe to call concurrently, and registrations
+/// never block. Concurrent queries see a best-effort view: each slot is read consistently,
+/// but a registration racing with a query may or may not be counted. Methods taking
+/// `&mut self`, such as [`reset_for`](Self::reset_for), need exclusive access.
+///
+/// # Clearing Data
+///
+/// Several methods discard data; they differ in what they zero:
+///
+/// | Method                                   | Slots | Latest observed time | Lifetime total | Spillover |
+/// |------------------------------------------|-------|----------------------|----------------|-----------|
+/// | [`clear`](Self::clear)                   | ✓     | ✓                    |                | ✓         |
+/// | [`reset`](Self::reset)                   | ✓     | ✓                    |                | ✓         |
+/// | [`reset_lifetime`](Self::reset_lifetime) |       |                      | ✓              |           |
+/// | [`reset_before`](Self::reset_before)     | Older |                      |                |           |
+/// | [`reset_for`](Self::reset_for)           | ✓     | ✓                    | ✓              | ✓         |
+///
+/// Only `reset_for` changes the geometry. Settings such as the
+/// [registration cap](Self::with_max_advance_per_register) and the [pause](Self::pause) state
+/// are never affected by clearing.
+///
 /// # Example
 ///
 /// ```rust
@@ -76,9 +501,31 @@ pub struct InvocationCounter {
     slots: Box<[Slot]>,
     slot_count_exp: u8,
     slot_size_exp: u8,
+    /// `2^slot_count_exp - 1`, used to map an interval to its slot with a mask
+    slot_count_mask: u64,
     max_current_time: AtomicU64,
+    lifetime_total: AtomicU64,
+    spillover: AtomicU64,
+    max_advance_per_register: u64,
+    paused: AtomicBool,
+    advance_time_while_paused: bool,
+    ordering_profile: OrderingProfile,
+    /// Bumped after every mutation, so readers can tell whether anything changed
+    generation: AtomicU64,
+    #[cfg(feature = "telemetry")]
+    out_of_order_count: AtomicU64,
+    #[cfg(feature = "telemetry")]
+    reset_count: AtomicU64,
+    #[cfg(feature = "telemetry")]
+    increment_count: AtomicU64,
 }
 
+// Fails to compile if a field ever makes the counter lose `Send` or `Sync`
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<InvocationCounter>();
+};
+
 impl InvocationCounter {
     /// Creates a new `InvocationCounter` with the specified configuration.
     ///
@@ -88,6 +535,12 @@ impl InvocationCounter {
     /// * `slot_size_exp` - Exponent for the size of each time interval (2^slot_size_exp time units per slot)
     ///
     /// The total sliding window size will be: `2^slot_count_exp × 2^slot_size_exp` time units.
+    /// With `slot_count_exp = 0`, the single slot only remembers the most recent interval,
+    /// which makes a plain per-interval counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected by [`try_new`](Self::try_new).
     ///
     /// # Examples
     ///
@@ -98,17 +551,211 @@ impl InvocationCounter {
     /// let counter = InvocationCounter::new(3, 4);
     /// ```
     pub fn new(slot_count_exp: u8, slot_size_exp: u8) -> Self {
-        let slots = (0..(1 << slot_count_exp))
+        Self::try_new(slot_count_exp, slot_size_exp).unwrap_or_else(|error| {
+            panic!("InvocationCounter::new({slot_count_exp}, {slot_size_exp}): {error}")
+        })
+    }
+
+    /// The largest accepted `slot_count_exp`: 2^24 slots of 64 bytes, 1 GiB of slots.
+    pub const MAX_SLOT_COUNT_EXP: u8 = 24;
+
+    /// Creates a new `InvocationCounter`, rejecting configurations that can't work.
+    ///
+    /// `slot_count_exp` must be at most [`MAX_SLOT_COUNT_EXP`](Self::MAX_SLOT_COUNT_EXP), since
+    /// every slot is allocated upfront, and `slot_size_exp` must be below `64`, so that a slot
+    /// covers a `u64`-representable number of time units.
+    ///
+    /// The window is exact as long as `slot_count_exp + slot_size_exp <= 63`. Larger sums are
+    /// accepted: the window then doesn't fit in a `u64`, and
+    /// [`saturating_window_size`](Self::saturating_window_size) reports it as `u64::MAX`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidConfig`] if either exponent is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// assert!(InvocationCounter::try_new(3, 4).is_ok());
+    /// assert!(InvocationCounter::try_new(40, 4).is_err());
+    /// assert!(InvocationCounter::try_new(3, 64).is_err());
+    /// ```
+    pub fn try_new(slot_count_exp: u8, slot_size_exp: u8) -> Result<Self, CounterError> {
+        check_geometry(slot_count_exp, slot_size_exp)?;
+
+        let slots = (0..1usize << slot_count_exp)
             .map(|_| Slot::new())
             .collect::<Vec<_>>()
             .into_boxed_slice();
 
-        Self {
+        Ok(Self {
             slots,
             slot_count_exp,
             slot_size_exp,
+            slot_count_mask: (1u64 << slot_count_exp) - 1,
             max_current_time: AtomicU64::new(0),
+            lifetime_total: AtomicU64::new(0),
+            spillover: AtomicU64::new(0),
+            max_advance_per_register: u64::MAX,
+            paused: AtomicBool::new(false),
+            advance_time_while_paused: false,
+            ordering_profile: OrderingProfile::Strict,
+            generation: AtomicU64::new(0),
+            #[cfg(feature = "telemetry")]
+            out_of_order_count: AtomicU64::new(0),
+            #[cfg(feature = "telemetry")]
+            reset_count: AtomicU64::new(0),
+            #[cfg(feature = "telemetry")]
+            increment_count: AtomicU64::new(0),
+        })
+    }
+
+    /// Creates a new `InvocationCounter` sized for a wall-clock window, with timestamps in
+    /// milliseconds.
+    ///
+    /// The slot size is the smallest power of two of milliseconds that is at least
+    /// `resolution`, and the slot count the smallest power of two such that the window covers at
+    /// least `total`. Exact durations rarely map to powers of two, so both are rounded up: the
+    /// resulting window is between `total` and twice `total` (or a single slot if `total` is
+    /// below the slot size), and slots may be up to twice as coarse as requested. Durations are
+    /// rounded up to whole milliseconds first, and a zero `resolution` means 1 ms slots. Use
+    /// [`slot_size`](Self::slot_size) and [`window_size`](Self::window_size) to read the
+    /// chosen geometry back.
+    ///
+    /// Timestamps passed to this counter must then be milliseconds, e.g. elapsed since a fixed
+    /// `Instant` or since the Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidConfig`] if the window would need more than
+    /// 2^[`MAX_SLOT_COUNT_EXP`](Self::MAX_SLOT_COUNT_EXP) slots, or if a slot would be too
+    /// large for a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// # use std::time::Duration;
+    /// let counter =
+    ///     InvocationCounter::from_window(Duration::from_secs(60), Duration::from_millis(100))
+    ///         .unwrap();
+    ///
+    /// assert_eq!(counter.slot_size(), 128); // 100 ms rounded up
+    /// assert_eq!(counter.num_slots(), 512); // 469 slots of 128 ms rounded up
+    /// assert_eq!(counter.window_size(), 65_536);
+    /// ```
+    pub fn from_window(total: Duration, resolution: Duration) -> Result<Self, CounterError> {
+        let to_millis = |duration: Duration| duration.as_nanos().div_ceil(1_000_000).max(1);
+
+        let slot_size = to_millis(resolution).next_power_of_two();
+        let slot_count = to_millis(total).div_ceil(slot_size).next_power_of_two();
+
+        // Both are powers of two of at most 2^128 / 2, so their exponents fit in a `u8`
+        Self::try_new(
+            slot_count.trailing_zeros() as u8,
+            slot_size.trailing_zeros() as u8,
+        )
+    }
+
+    /// Builds a counter holding `slots`, the `(interval_start, counter)` pair of every slot in
+    /// ring order, as captured from a counter with the same geometry.
+    ///
+    /// Returns [`CounterError::Decode`] if the number of slots doesn't match
+    /// `2^slot_count_exp`, or if a slot holds an interval that isn't aligned to
+    /// `2^slot_size_exp` or that doesn't map to that slot.
+    fn from_parts(
+        slot_count_exp: u8,
+        slot_size_exp: u8,
+        max_current_time: u64,
+        slots: impl ExactSizeIterator<Item = (u64, u32)>,
+    ) -> Result<Self, CounterError> {
+        let slot_count = 1usize.checked_shl(slot_count_exp.into());
+        if slot_count != Some(slots.len()) {
+            return Err(CounterError::Decode {
+                reason: format!("expected 2^{slot_count_exp} slots, found {}", slots.len()),
+            });
+        }
+
+        let mut counter = Self::try_new(slot_count_exp, slot_size_exp)?;
+        let slot_size_mask = (1 << slot_size_exp) - 1;
+        let slot_count_mask = counter.slot_count_mask;
+        for (index, (slot, (interval_start, count))) in
+            counter.slots.iter_mut().zip(slots).enumerate()
+        {
+            if interval_start != Slot::EMPTY && interval_start & slot_size_mask != 0 {
+                return Err(CounterError::Decode {
+                    reason: format!(
+                        "interval start {interval_start} isn't aligned to 2^{slot_size_exp}"
+                    ),
+                });
+            }
+            // Queries only look for an interval in the slot it maps to
+            let expected_index = (interval_start >> slot_size_exp) & slot_count_mask;
+            if count > 0 && expected_index != index as u64 {
+                return Err(CounterError::Decode {
+                    reason: format!("interval start {interval_start} found in slot {index}"),
+                });
+            }
+            *slot.interval_start.get_mut() = interval_start;
+            *slot.counter.get_mut() = count;
+        }
+        *counter.max_current_time.get_mut() = max_current_time;
+
+        Ok(counter)
+    }
+
+    /// Builds a counter holding `entries`, `(interval_start, count)` pairs such as the ones
+    /// returned by [`snapshot`](Self::snapshot), e.g. to restore a persisted counter after a
+    /// restart.
+    ///
+    /// Each entry is placed in the slot its interval maps to; interval starts that aren't
+    /// aligned to `2^slot_size_exp` are rounded down. When two entries map to the same slot,
+    /// the later interval wins, as it would have evicted the earlier one, and entries for the
+    /// same interval are added up. The latest observed time becomes the end of the latest
+    /// interval, so the window covers the restored intervals. Like with
+    /// [`CounterSnapshot::restore`], settings and the lifetime count start from their defaults.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected by [`try_new`](Self::try_new).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// counter.register(10);
+    /// counter.register(40);
+    ///
+    /// let restored = InvocationCounter::from_snapshot(3, 4, &counter.snapshot());
+    ///
+    /// assert_eq!(restored.snapshot(), counter.snapshot());
+    /// assert_eq!(restored.count_in(0, 48), 2);
+    /// ```
+    pub fn from_snapshot(slot_count_exp: u8, slot_size_exp: u8, entries: &[(u64, u32)]) -> Self {
+        let mut counter = Self::new(slot_count_exp, slot_size_exp);
+        let orderings = &counter.orderings().register;
+
+        let mut latest_interval = None;
+        let mut overflow = 0;
+        for &(interval_start, count) in entries {
+            let interval_start = interval_start >> slot_size_exp << slot_size_exp;
+            if interval_start == Slot::EMPTY {
+                continue;
+            }
+            let index = (interval_start >> slot_size_exp) & counter.slot_count_mask;
+            overflow +=
+                counter.slots[index as usize].merge(orderings, interval_start, count) as u64;
+            latest_interval = latest_interval.max(Some(interval_start));
+        }
+
+        if let Some(latest_interval) = latest_interval {
+            *counter.max_current_time.get_mut() = latest_interval | ((1 << slot_size_exp) - 1);
         }
+        *counter.spillover.get_mut() = overflow;
+
+        counter
     }
 
     /// Registers an invocation at the specified time.
@@ -116,6 +763,14 @@ impl InvocationCounter {
     /// This method is thread-safe. Multiple threads can call this method
     /// concurrently without external synchronization.
     ///
+    /// Each slot counts up to `u32::MAX` invocations: past that, the slot saturates instead of
+    /// wrapping around, and the excess is tracked by [`spillover`](Self::spillover).
+    ///
+    /// A timestamp whose interval already left the window ending at the latest observed time
+    /// is dropped, rather than overwriting the slot it maps to, which may hold a newer
+    /// interval. [`try_register`](Self::try_register) reports whether a registration was
+    /// recorded.
+    ///
     /// # Arguments
     ///
     /// * `current_time` - The timestamp when the invocation occurred
@@ -131,82 +786,92 @@ impl InvocationCounter {
     /// counter.register(25); // Different interval, uses different slot
     /// ```
     pub fn register(&self, current_time: u64) {
-        let interval_start = current_time >> self.slot_size_exp;
-
-        let slot_index = interval_start % (1 << self.slot_count_exp);
-
-        let interval_start = interval_start << self.slot_size_exp;
-
-        let slot = &self.slots[slot_index as usize];
-
-        let time_in_slot = slot.interval_start.load(Ordering::Acquire);
-        if time_in_slot == interval_start {
-            slot.counter.fetch_add(1, Ordering::Relaxed);
-        } else {
-            slot.interval_start.store(interval_start, Ordering::Release);
-            slot.counter.store(1, Ordering::Release);
-        }
-
-        let current_max_time = self.max_current_time.load(Ordering::Acquire);
-        if current_max_time < current_time {
-            self.max_current_time
-                .compare_exchange_weak(
-                    current_max_time,
-                    current_time,
-                    Ordering::Release,
-                    Ordering::Relaxed,
-                )
-                .ok();
-        }
+        self.register_n_observed(current_time, 1);
     }
 
-    /// Returns the slot count exponent used to create this counter.
+    /// Registers an invocation at a timestamp of any integer type.
     ///
-    /// The actual number of slots is `2^slot_count_exp()`.
+    /// The timestamp is converted to `u64`, the type every other method works with, so callers
+    /// holding `u32` ticks or `i64` epoch values don't need casts. A timestamp that doesn't fit
+    /// in a `u64`, i.e. a negative one or a `u128` above `u64::MAX`, is rejected. Otherwise this
+    /// behaves like [`try_register`](Self::try_register).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the invocation was registered, `false` if it was rejected.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use invocation_counter::InvocationCounter;
-    /// let counter = InvocationCounter::new(3, 4); // 8 slots, each covering 16 time units
-    /// assert_eq!(counter.slot_count_exp(), 3);
-    /// assert_eq!(1 << counter.slot_count_exp(), 8); // 2^3 = 8 slots
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// assert!(counter.register_t(10u32));
+    /// assert!(counter.register_t(12i64));
+    /// assert!(!counter.register_t(-5i64)); // Negative
+    ///
+    /// assert_eq!(counter.count_in_t(0i64, 16i64), 2);
     /// ```
-    pub fn slot_count_exp(&self) -> u8 {
-        self.slot_count_exp
+    pub fn register_t<T: TryInto<u64>>(&self, current_time: T) -> bool {
+        match current_time.try_into() {
+            Ok(current_time) => self.try_register(current_time),
+            Err(_) => false,
+        }
     }
 
-    /// Returns the slot size exponent used to create this counter.
+    /// Registers `count` invocations at the specified time at once.
     ///
-    /// The actual size of each time interval is `2^slot_size_exp()` time units.
+    /// This is equivalent to calling [`register`](Self::register) `count` times, but updates
+    /// the slot with a single atomic operation, which suits pre-aggregated metrics. The latest
+    /// observed time is updated exactly like `register` does, even when `count` is `0`, in
+    /// which case the slot itself is left untouched.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use invocation_counter::InvocationCounter;
-    /// let counter = InvocationCounter::new(3, 4); // 8 slots, each covering 16 time units
-    /// assert_eq!(counter.slot_size_exp(), 4);
-    /// assert_eq!(1 << counter.slot_size_exp(), 16); // 2^4 = 16 time units per slot
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_n(10, 5);
+    /// counter.register_n(12, 3);
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 8);
     /// ```
-    pub fn slot_size_exp(&self) -> u8 {
-        self.slot_size_exp
+    pub fn register_n(&self, current_time: u64, count: u32) {
+        self.register_n_observed(current_time, count);
     }
 
-    /// Returns the total number of invocations within the specified time range.
+    /// Registers an invocation of cost `weight` at the specified time.
     ///
-    /// Unlike `count()` which uses a fixed sliding window, this method allows querying
-    /// invocations within any arbitrary time range defined by `start_time` and `end_time`.
-    /// The method still respects the ring buffer's current valid data range.
+    /// Slots then hold summed weights rather than occurrences, e.g. work units of calls with
+    /// varying cost, and every query returns summed weights. This is
+    /// [`register_n`](Self::register_n) under a name that reads as a weight: slots saturate at
+    /// `u32::MAX` and the excess goes to [`spillover`](Self::spillover) the same way.
     ///
-    /// # Arguments
+    /// # Examples
     ///
-    /// * `start_time` - The start time of the range to query (inclusive)
-    /// * `end_time` - The end time of the range to query (exclusive)
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
     ///
-    /// # Returns
+    /// counter.register_weighted(10, 250); // An expensive call
+    /// counter.register_weighted(12, 1);   // A cheap one
     ///
-    /// The total number of invocations that occurred within the specified time range,
-    /// limited by the data currently available in the ring buffer.
+    /// assert_eq!(counter.count_in(0, 16), 251);
+    /// ```
+    pub fn register_weighted(&self, current_time: u64, weight: u32) {
+        self.register_n(current_time, weight);
+    }
+
+    /// Removes an invocation registered at the specified time, e.g. to count open connections
+    /// by registering on open and unregistering on close.
+    ///
+    /// The slot for `current_time`'s interval is decremented, saturating at `0`, only if it
+    /// still tracks that interval. Unlike registering, unregistering never rolls a slot over
+    /// nor advances the latest observed time: unregistering from an interval that was evicted,
+    /// or that was never registered into, is a no-op. The
+    /// [lifetime count](Self::lifetime_count) isn't decremented, and unregistrations are
+    /// ignored while the counter is [paused](Self::pause), like registrations.
     ///
     /// # Examples
     ///
@@ -215,259 +880,4884 @@ impl InvocationCounter {
     /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
     ///
     /// counter.register(10);
-    /// counter.register(25);
-    /// counter.register(100);
+    /// counter.register(12);
+    /// counter.unregister(14);
+    /// assert_eq!(counter.count_in(0, 16), 1);
     ///
-    /// assert_eq!(counter.count_in(0, 50), 2);   // First two invocations
-    /// assert_eq!(counter.count_in(20, 120), 2); // Second and third invocations
-    /// assert_eq!(counter.count_in(0, 200), 3);  // All invocations (if within ring buffer range)
+    /// counter.unregister(200); // Nothing registered in [192, 208): no-op
+    /// assert_eq!(counter.max_time(), 12);
     /// ```
-    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
-        if start_time >= end_time {
-            return 0;
+    pub fn unregister(&self, current_time: u64) {
+        if self.is_paused() {
+            return;
         }
 
-        let current_max_time = self.max_current_time.load(Ordering::Acquire);
-
-        // Calculate the ring buffer's valid range (same as count method)
-        let ring_end = ((current_max_time >> self.slot_size_exp) + 1) << self.slot_size_exp;
-        let ring_start =
-            ring_end.saturating_sub((1 << self.slot_size_exp) * (1 << self.slot_count_exp));
-        let ring_buffer_range = ring_start..ring_end;
+        let interval_start = current_time >> self.slot_size_exp << self.slot_size_exp;
+        let index = (current_time >> self.slot_size_exp) & self.slot_count_mask;
+        let removed =
+            self.slots[index as usize].unregister(&self.orderings().register, interval_start, 1);
+        if removed > 0 {
+            self.bump_generation();
+        }
+    }
 
-        // Calculate the requested range, aligning to slot boundaries
-        // start_time is inclusive: include the slot that contains start_time
-        let asked_start = start_time >> self.slot_size_exp << self.slot_size_exp;
-        // end_time is exclusive: find the slot that would contain end_time and use its start as boundary
-        // If end_time is exactly at a slot boundary, use that boundary
-        // Otherwise, use the start of the next slot after the slot containing end_time
-        let asked_end = if end_time & ((1 << self.slot_size_exp) - 1) == 0 {
-            // end_time is exactly at slot boundary
-            end_time
-        } else {
-            // end_time is within a slot, use start of next slot
-            ((end_time >> self.slot_size_exp) + 1) << self.slot_size_exp
-        };
-        let asked_range = asked_start..asked_end;
+    /// Registers an invocation at the specified time only if `predicate` returns `true`.
+    ///
+    /// The predicate is evaluated exactly once, before touching the counter. Combined with a
+    /// sampler this records a fraction of events (e.g. 1-in-N) directly at the call site.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the invocation was registered, `false` if the predicate rejected it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// // Record every third event
+    /// for i in 0..9u64 {
+    ///     counter.register_if(i, || i % 3 == 0);
+    /// }
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 3);
+    /// ```
+    pub fn register_if(&self, current_time: u64, predicate: impl FnOnce() -> bool) -> bool {
+        if !predicate() {
+            return false;
+        }
 
-        // Find the intersection of ring buffer range and requested range
-        let valid_range = ring_buffer_range.start.max(asked_range.start)
-            ..ring_buffer_range.end.min(asked_range.end);
+        self.register(current_time);
+        true
+    }
 
-        let mut count = 0;
-        for slot in &self.slots {
-            let time_in_slot = slot.interval_start.load(Ordering::Acquire);
-            if valid_range.contains(&time_in_slot) {
-                count += slot.counter.load(Ordering::Acquire);
-            }
+    /// Registers an invocation at `now` only if fewer than `limit` invocations happened in the
+    /// last `window` time units, making the counter a sliding-window rate limiter.
+    ///
+    /// The check counts `[now + 1 - window, now + 1)` like [`count_in`](Self::count_in), so it
+    /// is as approximate as any query: the range is aligned to slot boundaries, and `window` is
+    /// clamped to the counter's [window size](Self::window_size). Slots should therefore be
+    /// much smaller than `window`. The check and the registration are separate atomic steps,
+    /// so threads racing for the last permits may together overshoot `limit` slightly. A
+    /// denied call costs a single query and leaves the counter untouched.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the invocation was allowed and registered, `false` if it was denied, or
+    /// [rejected](Self::try_register) by the counter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let limiter = InvocationCounter::new(6, 0); // 64 slots × 1 unit
+    ///
+    /// // At most 2 calls per 10 units
+    /// assert!(limiter.try_acquire(100, 2, 10));
+    /// assert!(limiter.try_acquire(101, 2, 10));
+    /// assert!(!limiter.try_acquire(105, 2, 10));
+    /// assert!(limiter.try_acquire(110, 2, 10)); // The call at 100 left the window
+    /// ```
+    pub fn try_acquire(&self, now: u64, limit: u32, window: u64) -> bool {
+        let end_time = now.saturating_add(1);
+        let window = window.min(self.saturating_window_size());
+        if self.count_in(end_time.saturating_sub(window), end_time) >= limit {
+            return false;
         }
 
-        count
+        self.try_register(now)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::Arc;
-    use std::thread;
 
-    #[test]
-    fn test_basic_functionality() {
-        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
-        let counter = InvocationCounter::new(2, 3);
+    /// Registers an invocation at `now` and returns the number of invocations in the last
+    /// `window` time units, including this one, e.g. for leaky-bucket style limiters.
+    ///
+    /// The count covers `[now + 1 - window, now + 1)` like [`try_acquire`](Self::try_acquire):
+    /// the range ends at `now` rather than at the latest observed time, so unlike
+    /// `register` followed by [`count_last`](Self::count_last) it doesn't need a separate load
+    /// of the latest time to position the range. `window` is clamped to the counter's
+    /// [window size](Self::window_size) and the range is aligned to slot boundaries.
+    ///
+    /// The registration and the count are two steps, not one atomic operation:
+    ///
+    /// - The count always includes this call's invocation, unless the registration was
+    ///   [rejected](Self::try_register): a thread always observes its own increments.
+    /// - Invocations registered concurrently by other threads may or may not be included, so
+    ///   two threads racing may both count each other's invocation, or only their own.
+    /// - No invocation is ever counted twice, and the count never includes invocations
+    ///   registered after this call returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(6, 0); // 64 slots × 1 unit
+    ///
+    /// assert_eq!(counter.register_and_count(100, 10), 1);
+    /// assert_eq!(counter.register_and_count(105, 10), 2);
+    /// assert_eq!(counter.register_and_count(112, 10), 2); // The call at 100 left the window
+    /// ```
+    pub fn register_and_count(&self, now: u64, window: u64) -> u32 {
+        self.register(now);
+
+        let end_time = now.saturating_add(1);
+        let window = window.min(self.saturating_window_size());
+        self.count_in(end_time.saturating_sub(window), end_time)
+    }
+
+    /// Registers `n` invocations at the specified time and reports what happened to the slot.
+    ///
+    /// This is the most general registration primitive: it adds `n` to the slot for
+    /// `current_time`'s interval and, if that slot was still tracking an older interval, resets
+    /// it and reports how many invocations were evicted from it.
+    ///
+    /// The slot reset is performed under a per-slot lock that only rollovers take, so
+    /// when several threads race to roll a slot over, exactly one of them wins and reports the
+    /// evicted count; the others see the new interval and simply add to it.
+    ///
+    /// A registration rejected by the
+    /// [registration cap](Self::with_max_advance_per_register) reports nothing added or evicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_time` - The timestamp when the invocations occurred
+    /// * `n` - The number of invocations to register
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{InvocationCounter, RegisterOutcome};
+    /// let counter = InvocationCounter::new(1, 2); // 2 slots × 4 units = 8-unit window
+    ///
+    /// counter.register_n_observed(0, 3);
+    /// let outcome = counter.register_n_observed(8, 5); // Reuses the slot of interval 0
+    ///
+    /// assert_eq!(outcome, RegisterOutcome { added: 5, evicted: 3 });
+    /// ```
+    pub fn register_n_observed(&self, current_time: u64, n: u32) -> RegisterOutcome {
+        self.try_update(current_time, SlotUpdate::Add(n)).map_or(
+            RegisterOutcome {
+                added: 0,
+                evicted: 0,
+            },
+            |(outcome, _)| outcome,
+        )
+    }
+
+    /// Registers an invocation at the specified time, unless it is rejected.
+    ///
+    /// A registration is rejected when its timestamp is too old to be recorded, because its
+    /// interval already left the window ending at the latest observed time, when it would
+    /// advance the latest observed time by more than the cap set with
+    /// [`with_max_advance_per_register`](Self::with_max_advance_per_register), or while
+    /// registration is [paused](Self::pause). Otherwise this behaves like
+    /// [`register`](Self::register), which drops rejected registrations silently.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the invocation was registered, `false` if it was rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4).with_max_advance_per_register(1_000);
+    ///
+    /// assert!(counter.try_register(100));
+    /// assert!(!counter.try_register(u64::MAX)); // Too far ahead of 100
+    /// assert!(counter.try_register(200));
+    ///
+    /// assert_eq!(counter.count_in(0, 256), 2);
+    /// ```
+    pub fn try_register(&self, current_time: u64) -> bool {
+        self.try_update(current_time, SlotUpdate::Add(1)).is_some()
+    }
+
+    /// Registers an invocation at the specified time, reporting whether it was lost to a
+    /// saturated slot.
+    ///
+    /// This behaves like [`register`](Self::register), but where `register` silently clamps a
+    /// slot at `u32::MAX` and moves the excess to the [spillover](Self::spillover), this
+    /// returns an error, so callers that can't tolerate approximate counts find out.
+    /// Registrations rejected by the counter, e.g. while [paused](Self::pause), are dropped
+    /// like with `register` and return `Ok`: use [`try_register`](Self::try_register) to
+    /// detect them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::Overflow`] if the slot counter was already at `u32::MAX`, in
+    /// which case the invocation was only added to the spillover.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{CounterError, InvocationCounter};
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_n(10, u32::MAX - 1);
+    /// assert_eq!(counter.checked_register(12), Ok(()));
+    /// assert_eq!(
+    ///     counter.checked_register(14),
+    ///     Err(CounterError::Overflow { interval_start: 0 })
+    /// );
+    /// ```
+    pub fn checked_register(&self, current_time: u64) -> Result<(), CounterError> {
+        match self.try_update(current_time, SlotUpdate::Add(1)) {
+            Some((_, overflow)) if overflow > 0 => Err(CounterError::Overflow {
+                interval_start: current_time >> self.slot_size_exp << self.slot_size_exp,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Raises the count of the interval containing `current_time` to `observed_count`,
+    /// leaving it unchanged if it is already at least that high.
+    ///
+    /// Unlike [`register_n_observed`](Self::register_n_observed), which adds, this records an
+    /// absolute per-interval count, so replaying the same observation is idempotent. This
+    /// suits reconciling from an upstream that reports "N events in this interval" with
+    /// at-least-once delivery. The [lifetime count](Self::lifetime_count) grows by how much the
+    /// interval was raised. Rejected registrations are dropped like with
+    /// [`register`](Self::register).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_max(10, 5);
+    /// counter.register_max(10, 5); // Replayed batch
+    /// counter.register_max(12, 3); // Lower count for the same interval
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 5);
+    /// ```
+    pub fn register_max(&self, current_time: u64, observed_count: u32) {
+        self.try_update(current_time, SlotUpdate::Max(observed_count));
+    }
+
+    /// Applies `update` to the slot for the specified time, returning `None` if the
+    /// registration was rejected.
+    fn try_update(&self, current_time: u64, update: SlotUpdate) -> Option<(RegisterOutcome, u32)> {
+        let interval_start = current_time >> self.slot_size_exp << self.slot_size_exp;
+
+        self.try_update_at(interval_start, current_time - interval_start, update)
+    }
+
+    /// Applies `update` to the slot for `interval_start + offset_in_slot`, where
+    /// `interval_start` is slot-aligned and `offset_in_slot` is smaller than the slot size.
+    ///
+    /// Returns the outcome and the number of invocations that didn't fit in the slot's
+    /// counter, or `None` if the registration was rejected.
+    fn try_update_at(
+        &self,
+        interval_start: u64,
+        offset_in_slot: u64,
+        update: SlotUpdate,
+    ) -> Option<(RegisterOutcome, u32)> {
+        let current_time = interval_start + offset_in_slot;
+        let orderings = &self.orderings().register;
+
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        if current_time.saturating_sub(current_max_time) > self.max_advance_per_register {
+            return None;
+        }
+        // An interval that already left the window would only clobber the slot it maps to,
+        // which may hold a newer interval
+        let window_start = ring_range(
+            current_max_time,
+            self.slot_size_exp,
+            self.saturating_window_size(),
+        )
+        .start;
+        if interval_start < window_start {
+            return None;
+        }
+
+        if self.paused.load(Ordering::Relaxed) {
+            if self.advance_time_while_paused {
+                self.advance_time(current_time);
+            }
+            return None;
+        }
+
+        let slot_index = ((interval_start >> self.slot_size_exp) & self.slot_count_mask) as usize;
+
+        debug_assert!(
+            slot_index < self.slots.len(),
+            "slot index {slot_index} out of bounds for {} slots",
+            self.slots.len()
+        );
+        #[cfg(feature = "unsafe-unchecked")]
+        // SAFETY: `slots` always holds exactly `slot_count_mask + 1` slots (see `new` and
+        // `reset_for`), and masking with `slot_count_mask` keeps the index below that.
+        let slot = unsafe { self.slots.get_unchecked(slot_index) };
+        #[cfg(not(feature = "unsafe-unchecked"))]
+        let slot = &self.slots[slot_index];
+
+        let (rolled_over, added, overflow) = match update {
+            // Nothing to add: leave the slot, and whatever interval it holds, untouched
+            SlotUpdate::Add(0) => (None, 0, 0),
+            SlotUpdate::Add(n) => {
+                let (rolled_over, overflow) = slot.register(orderings, interval_start, n);
+                if overflow > 0 {
+                    self.spillover
+                        .fetch_add(overflow as u64, orderings.totals_update);
+                }
+                (rolled_over, n, overflow)
+            }
+            SlotUpdate::Max(count) => {
+                let (rolled_over, raised_by) = slot.register_max(orderings, interval_start, count);
+                (rolled_over, raised_by, 0)
+            }
+        };
+
+        self.lifetime_total
+            .fetch_add(added as u64, orderings.totals_update);
+
+        #[cfg(feature = "telemetry")]
+        {
+            if current_time < current_max_time {
+                self.out_of_order_count.fetch_add(1, Ordering::Relaxed);
+            }
+            if rolled_over.is_some() {
+                self.reset_count.fetch_add(1, Ordering::Relaxed);
+            } else if added > 0 {
+                self.increment_count.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        // Retry until the latest observed time is at least `current_time`, so a concurrent
+        // update to a smaller time can't make this one get lost
+        let mut observed_max_time = current_max_time;
+        while observed_max_time < current_time {
+            match self.max_current_time.compare_exchange_weak(
+                observed_max_time,
+                current_time,
+                orderings.max_time_update_success,
+                orderings.max_time_update_failure,
+            ) {
+                Ok(_) => break,
+                Err(actual) => observed_max_time = actual,
+            }
+        }
+
+        self.bump_generation();
+
+        Some((
+            RegisterOutcome {
+                added,
+                evicted: rolled_over.unwrap_or(0),
+            },
+            overflow,
+        ))
+    }
+
+    /// Pauses registration.
+    ///
+    /// While paused, every registration is dropped: [`try_register`](Self::try_register)
+    /// returns `false` and the other register methods do nothing. Queries keep working over
+    /// the data recorded before the pause. Whether dropped registrations still advance the
+    /// latest observed time is configured with
+    /// [`with_time_advance_while_paused`](Self::with_time_advance_while_paused).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(1);
+    /// counter.pause();
+    /// assert!(!counter.try_register(2)); // Dropped
+    /// counter.resume();
+    /// assert!(counter.try_register(3));
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 2);
+    /// ```
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Resumes registration after a [`pause`](Self::pause).
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if registration is currently [paused](Self::pause).
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether registrations dropped while [paused](Self::pause) still advance the latest
+    /// observed time.
+    ///
+    /// When enabled, the window keeps moving during a pause as if the dropped invocations had
+    /// only advanced time, so old intervals age out at the usual pace. When disabled (the
+    /// default), a pause freezes the window as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4).with_time_advance_while_paused(true);
+    ///
+    /// counter.register(10);
+    /// counter.pause();
+    /// counter.register(1_000); // Dropped, but the window moves to time 1000
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 0);
+    /// ```
+    pub fn with_time_advance_while_paused(mut self, advance: bool) -> Self {
+        self.advance_time_while_paused = advance;
+        self
+    }
+
+    /// Sets the memory orderings used by registrations and queries.
+    ///
+    /// The default, [`OrderingProfile::Strict`], uses acquire/release orderings;
+    /// [`OrderingProfile::Fast`] relaxes every ordering the slot seqlock doesn't depend on, for
+    /// throughput on weakly-ordered architectures at the cost of queries lagging slightly
+    /// behind concurrent registrations. See [`OrderingProfile`] for the exact orderings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{InvocationCounter, OrderingProfile};
+    /// let counter = InvocationCounter::new(3, 4).with_ordering_profile(OrderingProfile::Fast);
+    ///
+    /// counter.register(10);
+    /// assert_eq!(counter.count_in(0, 16), 1);
+    /// assert_eq!(counter.ordering_profile(), OrderingProfile::Fast);
+    /// ```
+    pub fn with_ordering_profile(mut self, profile: OrderingProfile) -> Self {
+        self.ordering_profile = profile;
+        self
+    }
+
+    /// Returns the memory ordering profile of this counter, set with
+    /// [`with_ordering_profile`](Self::with_ordering_profile).
+    pub fn ordering_profile(&self) -> OrderingProfile {
+        self.ordering_profile
+    }
+
+    /// Returns the memory orderings of this counter's profile.
+    fn orderings(&self) -> &'static OrderingReport {
+        self.ordering_profile.orderings_ref()
+    }
+
+    /// Copies the settings of `other` (registration cap, pause behavior and ordering profile)
+    /// into this counter.
+    ///
+    /// The generation is carried over and bumped as well, so it keeps increasing when a
+    /// counter is replaced by a reconfigured one.
+    fn copy_settings_from(&mut self, other: &InvocationCounter) {
+        self.max_advance_per_register = other.max_advance_per_register;
+        self.advance_time_while_paused = other.advance_time_while_paused;
+        self.ordering_profile = other.ordering_profile;
+        *self.paused.get_mut() = other.is_paused();
+        *self.generation.get_mut() = other.generation() + 1;
+    }
+
+    /// Registers an invocation given its pre-computed interval and offset within that interval.
+    ///
+    /// This is the lower-level primitive behind [`register`](Self::register):
+    /// `register_at(interval_start, offset_in_slot)` is equivalent to
+    /// `register(interval_start + offset_in_slot)`. Callers that already bucket time themselves
+    /// can use it to skip recomputing the interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_start` - The start timestamp of the interval, a multiple of the slot size
+    ///   (2^`slot_size_exp`)
+    /// * `offset_in_slot` - The position of the invocation within the interval
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidArgument`] if `interval_start` isn't aligned to a slot
+    /// boundary or `offset_in_slot` isn't smaller than the slot size. Nothing is registered in
+    /// that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 16 time units per slot
+    ///
+    /// counter.register_at(32, 5).unwrap(); // Same as `register(37)`
+    /// assert_eq!(counter.count_in(32, 48), 1);
+    ///
+    /// assert!(counter.register_at(32, 16).is_err()); // Offset past the slot
+    /// assert!(counter.register_at(33, 0).is_err()); // Unaligned interval
+    /// ```
+    pub fn register_at(
+        &self,
+        interval_start: u64,
+        offset_in_slot: u64,
+    ) -> Result<(), CounterError> {
+        let slot_mask = (1 << self.slot_size_exp) - 1;
+        if interval_start & slot_mask != 0 {
+            return Err(CounterError::InvalidArgument {
+                reason: "interval_start is not aligned to a slot boundary",
+            });
+        }
+        if offset_in_slot > slot_mask {
+            return Err(CounterError::InvalidArgument {
+                reason: "offset_in_slot is not smaller than the slot size",
+            });
+        }
+
+        self.try_update_at(interval_start, offset_in_slot, SlotUpdate::Add(1));
+        Ok(())
+    }
+
+    /// Limits how far a single registration can advance the latest observed time.
+    ///
+    /// A registration at a timestamp far in the future (e.g. `u64::MAX` from a buggy or
+    /// malicious caller) would otherwise move the window so far ahead that every later,
+    /// legitimate registration falls outside of it, effectively zeroing all queries. With a cap,
+    /// registrations more than `max_advance` time units past the latest observed time are
+    /// rejected: [`try_register`](Self::try_register) returns `false` and the other register
+    /// methods drop them silently.
+    ///
+    /// The cap also applies to the very first registration, which is measured from time `0`,
+    /// so a fresh counter refuses `u64::MAX` too. Counters fed with absolute timestamps should
+    /// therefore establish a baseline with [`advance_time`](Self::advance_time), which isn't
+    /// capped, before registering.
+    ///
+    /// By default there is no cap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// // Reject registrations more than one hour (in ms) ahead of the latest one
+    /// let counter = InvocationCounter::new(6, 10).with_max_advance_per_register(3_600_000);
+    ///
+    /// // Timestamps are absolute, so start from the current time
+    /// counter.advance_time(1_700_000_000_000);
+    ///
+    /// assert!(counter.try_register(1_700_000_000_500));
+    /// assert!(!counter.try_register(u64::MAX));
+    /// ```
+    pub fn with_max_advance_per_register(mut self, max_advance: u64) -> Self {
+        self.max_advance_per_register = max_advance;
+        self
+    }
+
+    /// Advances the counter's notion of the current time to `now` without registering anything.
+    ///
+    /// Queries only consider slots within the window ending at the latest observed time, which
+    /// otherwise only moves forward on `register`. Calling this during quiet periods lets old
+    /// intervals age out of the window even when no invocations occur. Times earlier than the
+    /// latest observed time are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// assert_eq!(counter.count_in(0, 16), 1);
+    ///
+    /// counter.advance_time(1_000); // The registration at time 10 is now out of the window
+    /// assert_eq!(counter.count_in(0, 16), 0);
+    /// ```
+    pub fn advance_time(&self, now: u64) {
+        if self.max_current_time.fetch_max(now, Ordering::AcqRel) < now {
+            self.bump_generation();
+        }
+    }
+
+    /// Fast-forwards the window to `now` without registering anything.
+    ///
+    /// This is the same operation as [`advance_time`](Self::advance_time), which should be used
+    /// instead: intervals that leave the window stop being counted, and no counter is
+    /// incremented.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![allow(deprecated)]
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.advance(10_000);
+    ///
+    /// assert_eq!(counter.count_last(128), 0);
+    /// ```
+    #[deprecated(note = "use `advance_time`, which this delegates to")]
+    pub fn advance(&self, now: u64) {
+        self.advance_time(now);
+    }
+
+    /// Records that this counter was mutated.
+    ///
+    /// Must be called after the mutation is visible, so that a reader that observes the new
+    /// generation also observes the mutation.
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Returns a number that increases every time the data held by this counter changes.
+    ///
+    /// Every registration, time advance, [`clear`](Self::clear),
+    /// [`reset_lifetime`](Self::reset_lifetime), [`reset_for`](Self::reset_for),
+    /// [`replace_slot_size`](Self::replace_slot_size) and
+    /// [`swap_contents`](Self::swap_contents) bumps the generation, while queries never do. If
+    /// two reads return the same generation, any query result computed in between is still
+    /// up to date, so caches and snapshots can poll this cheaply to decide whether to refresh.
+    /// Rejected registrations (paused or capped) don't change the data and don't bump it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// let before = counter.generation();
+    /// counter.register(10);
+    /// assert!(counter.generation() > before);
+    ///
+    /// let after = counter.generation();
+    /// counter.count_in(0, 16);
+    /// assert_eq!(counter.generation(), after);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Returns the total number of invocations registered since this counter was created.
+    ///
+    /// Unlike the windowed queries, this count never decays: it is incremented on every
+    /// `register` regardless of slot reuse, so it keeps growing as old intervals are evicted
+    /// from the ring buffer. It is only cleared by [`reset_lifetime`](Self::reset_lifetime),
+    /// and it is not exchanged by [`swap_contents`](Self::swap_contents).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(1, 2); // 2 slots × 4 units = 8-unit window
+    ///
+    /// counter.register(0);
+    /// counter.register(100); // Evicts the first registration from the window
+    ///
+    /// assert_eq!(counter.count_in(0, 101), 1);
+    /// assert_eq!(counter.lifetime_count(), 2);
+    /// ```
+    pub fn lifetime_count(&self) -> u64 {
+        self.lifetime_total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of invocations that didn't fit in their slot's counter.
+    ///
+    /// Slot counters saturate at `u32::MAX` rather than wrapping around. Whenever a registration
+    /// would push a slot past that value, the slot stays at `u32::MAX` and the excess is added
+    /// to this single shared spillover total, so no invocation is silently lost.
+    ///
+    /// The spillover is **not** windowed: it isn't attributed to any interval, it isn't
+    /// included in [`count_in`](Self::count_in) and friends, and it isn't evicted when the
+    /// saturated slot is reused. Adding it to a windowed count restores exactness as long as
+    /// the saturated interval is still within the queried range. This is cheaper than widening
+    /// every slot to 64 bits, at the cost of per-slot exactness once a slot saturates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_n_observed(10, u32::MAX);
+    /// counter.register_n_observed(10, 5);
+    ///
+    /// assert_eq!(counter.count_in(0, 16), u32::MAX); // The slot is saturated
+    /// assert_eq!(counter.spillover(), 5);
+    /// ```
+    pub fn spillover(&self) -> u64 {
+        self.spillover.load(Ordering::Relaxed)
+    }
+
+    /// Returns the latest time observed by this counter, `0` if none.
+    ///
+    /// This is the largest timestamp ever passed to [`register`](Self::register) and the
+    /// other registration methods, or to [`advance_time`](Self::advance_time): the window
+    /// always ends right after it. Registrations rejected by the
+    /// [registration cap](Self::with_max_advance_per_register) don't move it, and
+    /// [`clear`](Self::clear) and [`reset`](Self::reset) set it back to `0`. Use it to align
+    /// queries with the counter's notion of now, or to detect a counter that stopped receiving
+    /// registrations.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(40);
+    /// counter.register(10); // Out of order: the latest time doesn't go back
+    ///
+    /// assert_eq!(counter.max_time(), 40);
+    /// ```
+    pub fn max_time(&self) -> u64 {
+        self.max_current_time
+            .load(self.orderings().count_in.max_time_load)
+    }
+
+    /// Resets the lifetime total returned by [`lifetime_count`](Self::lifetime_count) to zero.
+    ///
+    /// The slots and the windowed counts are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(10);
+    /// counter.reset_lifetime();
+    ///
+    /// assert_eq!(counter.lifetime_count(), 0);
+    /// assert_eq!(counter.count_in(0, 16), 1);
+    /// ```
+    pub fn reset_lifetime(&self) {
+        self.lifetime_total.store(0, Ordering::Relaxed);
+        self.bump_generation();
+    }
+
+    /// Returns how many registrations arrived with a timestamp earlier than the latest one
+    /// observed so far.
+    ///
+    /// Out-of-order timestamps are still registered as long as their interval is within the
+    /// window, while older ones are dropped and not counted here. A growing value usually
+    /// points at clock skew or misordered ingestion.
+    ///
+    /// Only available with the `telemetry` feature, which adds an extra atomic update to every
+    /// out-of-order registration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(20);
+    /// counter.register(10); // Earlier than 20
+    ///
+    /// assert_eq!(counter.out_of_order_count(), 1);
+    /// ```
+    #[cfg(feature = "telemetry")]
+    pub fn out_of_order_count(&self) -> u64 {
+        self.out_of_order_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many registrations rolled a slot over to a new interval for every
+    /// registration that incremented a slot already tracking its interval.
+    ///
+    /// A high ratio means most registrations land in a fresh interval, i.e. the slot size is
+    /// too small for the registration rate and a larger `slot_size_exp` would lose less
+    /// information per slot. Returns `0.0` before the first registration and
+    /// `f64::INFINITY` if every registration so far reset a slot.
+    ///
+    /// Only available with the `telemetry` feature, which adds an extra atomic update to every
+    /// registration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(20); // Rolls the slot over to the interval [16, 32)
+    /// counter.register(21);
+    /// counter.register(22);
+    ///
+    /// assert_eq!(counter.reset_increment_ratio(), 0.5);
+    /// ```
+    #[cfg(feature = "telemetry")]
+    pub fn reset_increment_ratio(&self) -> f64 {
+        let resets = self.reset_count.load(Ordering::Relaxed);
+        let increments = self.increment_count.load(Ordering::Relaxed);
+        match (resets, increments) {
+            (0, _) => 0.0,
+            (_, 0) => f64::INFINITY,
+            _ => resets as f64 / increments as f64,
+        }
+    }
+
+    /// Returns the slot count exponent used to create this counter.
+    ///
+    /// The actual number of slots is `2^slot_count_exp()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots, each covering 16 time units
+    /// assert_eq!(counter.slot_count_exp(), 3);
+    /// assert_eq!(1 << counter.slot_count_exp(), 8); // 2^3 = 8 slots
+    /// ```
+    pub fn slot_count_exp(&self) -> u8 {
+        self.slot_count_exp
+    }
+
+    /// Returns the slot size exponent used to create this counter.
+    ///
+    /// The actual size of each time interval is `2^slot_size_exp()` time units.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots, each covering 16 time units
+    /// assert_eq!(counter.slot_size_exp(), 4);
+    /// assert_eq!(1 << counter.slot_size_exp(), 16); // 2^4 = 16 time units per slot
+    /// ```
+    pub fn slot_size_exp(&self) -> u8 {
+        self.slot_size_exp
+    }
+
+    /// Returns the number of slots, `2^slot_count_exp`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// assert_eq!(InvocationCounter::new(3, 4).num_slots(), 8);
+    /// ```
+    pub fn num_slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of time units covered by each slot, `2^slot_size_exp`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// assert_eq!(InvocationCounter::new(3, 4).slot_size(), 16);
+    /// ```
+    pub fn slot_size(&self) -> u64 {
+        1 << self.slot_size_exp
+    }
+
+    /// Returns the size of the sliding window in time units, `num_slots() × slot_size()`,
+    /// saturating at `u64::MAX`.
+    ///
+    /// This delegates to [`saturating_window_size`](Self::saturating_window_size), which
+    /// explains when the window doesn't fit in a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// assert_eq!(counter.window_size(), counter.num_slots() as u64 * counter.slot_size());
+    /// ```
+    pub fn window_size(&self) -> u64 {
+        self.saturating_window_size()
+    }
+
+    /// Returns the size of the sliding window in time units, saturating at `u64::MAX`.
+    ///
+    /// The window spans `2^slot_count_exp × 2^slot_size_exp` time units. When that product
+    /// doesn't fit in a `u64` (`slot_count_exp + slot_size_exp >= 64`), `u64::MAX` is returned:
+    /// such a window reaches back to time `0` from any point in time, so every interval still
+    /// held by a slot is considered in-window by the queries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// assert_eq!(InvocationCounter::new(3, 4).saturating_window_size(), 128);
+    /// assert_eq!(InvocationCounter::new(1, 63).saturating_window_size(), u64::MAX);
+    /// ```
+    pub fn saturating_window_size(&self) -> u64 {
+        window_size(self.slot_count_exp, self.slot_size_exp)
+    }
+
+    /// Returns `true` if this counter's sliding window spans at least `window` time units.
+    ///
+    /// The window size is `2^slot_count_exp × 2^slot_size_exp`. Use this to pick, from a set of
+    /// counters, one that can answer a query over the last `window` time units.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// assert!(counter.covers_at_least(128));
+    /// assert!(!counter.covers_at_least(129));
+    /// ```
+    pub fn covers_at_least(&self, window: u64) -> bool {
+        // A window that saturates at `u64::MAX` is larger than any `u64` value
+        self.saturating_window_size() >= window
+    }
+
+    /// Returns `true` if this counter has a finer time resolution than `other`, i.e. each of
+    /// its slots covers fewer time units.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let fine = InvocationCounter::new(6, 2);   // 64 slots × 4 units
+    /// let coarse = InvocationCounter::new(3, 5); // 8 slots × 32 units
+    ///
+    /// assert!(fine.finer_than(&coarse));
+    /// assert!(!coarse.finer_than(&fine));
+    /// assert!(!fine.finer_than(&fine));
+    /// ```
+    pub fn finer_than(&self, other: &InvocationCounter) -> bool {
+        self.slot_size_exp < other.slot_size_exp
+    }
+
+    /// Returns the total number of invocations within the sliding window ending at the latest
+    /// observed time.
+    ///
+    /// This is the most common query, "how many invocations in the last
+    /// 2^slot_count_exp × 2^slot_size_exp time units": it is equivalent to
+    /// [`count_in`](Self::count_in) over `[max_current_time - window_size + 1,
+    /// max_current_time + 1)`, without having to compute the bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(100);
+    /// assert_eq!(counter.count(), 2);
+    ///
+    /// counter.register(200); // The registration at time 10 leaves the window
+    /// assert_eq!(counter.count(), 2);
+    /// ```
+    pub fn count(&self) -> u32 {
+        self.count_last(self.saturating_window_size())
+    }
+
+    /// Returns the total number of invocations within the last `window` time units, ending at
+    /// the latest observed time.
+    ///
+    /// This is [`count_in`](Self::count_in) over `[max_current_time - window + 1,
+    /// max_current_time + 1)`: `window` units including the latest observed time, so
+    /// `count_last(1)` counts the interval containing it. A `window` larger than the counter's
+    /// [window size](Self::window_size) is clamped to it, since older data isn't held anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(70);
+    /// counter.register(100);
+    ///
+    /// assert_eq!(counter.count_last(16), 1);     // [85, 101), aligned to [80, 112)
+    /// assert_eq!(counter.count_last(40), 2);     // [61, 101), aligned to [48, 112)
+    /// assert_eq!(counter.count_last(10_000), 3); // The whole window
+    /// ```
+    pub fn count_last(&self, window: u64) -> u32 {
+        let current_max_time = self
+            .max_current_time
+            .load(self.orderings().count_in.max_time_load);
+        let end_time = current_max_time.saturating_add(1);
+        let window = window.min(self.saturating_window_size());
+
+        self.count_in(end_time.saturating_sub(window), end_time)
+    }
+
+    /// Returns the total number of invocations retained by the ring buffer, as a `u64`.
+    ///
+    /// Only slots holding intervals of the current window contribute: slots whose interval
+    /// aged out but weren't reused yet are ignored. This is what
+    /// `count_in_u64(0, u64::MAX)` returns, without having to spell out the range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register_n(10, 3);
+    /// counter.register_n(100, 2);
+    /// assert_eq!(counter.total(), 5);
+    ///
+    /// counter.advance_time(200); // The registration at time 10 leaves the window
+    /// assert_eq!(counter.total(), 2);
+    /// ```
+    pub fn total(&self) -> u64 {
+        let ring_range = self.ring_range();
+        self.count_in_u64(ring_range.start, ring_range.end)
+    }
+
+    /// Returns `true` if no slot of the current window holds any invocation.
+    ///
+    /// Like [`total`](Self::total), slots whose interval aged out of the window are ignored,
+    /// so a counter becomes empty again once all its invocations are old enough.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// assert!(counter.is_empty());
+    ///
+    /// counter.register(10);
+    /// assert!(!counter.is_empty());
+    ///
+    /// counter.advance_time(200);
+    /// assert!(counter.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        let ring_range = self.ring_range();
+
+        let mut empty = true;
+        self.scan_in(ring_range.start, ring_range.end, |_, slot_count| {
+            empty &= slot_count == 0
+        });
+
+        empty
+    }
+
+    /// Returns the total number of invocations within the specified time range.
+    ///
+    /// Unlike [`count`](Self::count) which uses a fixed sliding window, this method allows querying
+    /// invocations within any arbitrary time range defined by `start_time` and `end_time`.
+    /// The method still respects the ring buffer's current valid data range.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - The start time of the range to query (inclusive)
+    /// * `end_time` - The end time of the range to query (exclusive)
+    ///
+    /// # Returns
+    ///
+    /// The total number of invocations that occurred within the specified time range,
+    /// limited by the data currently available in the ring buffer. Saturates at `u32::MAX`:
+    /// use [`count_in_u64`](Self::count_in_u64) when the total may exceed it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(25);
+    /// counter.register(100);
+    ///
+    /// assert_eq!(counter.count_in(0, 50), 2);   // First two invocations
+    /// assert_eq!(counter.count_in(20, 120), 2); // Second and third invocations
+    /// assert_eq!(counter.count_in(0, 200), 3);  // All invocations (if within ring buffer range)
+    /// ```
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        u32::try_from(self.count_in_u64(start_time, end_time)).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the total number of invocations within each of the specified time ranges.
+    ///
+    /// The result matches calling [`count_in`](Self::count_in) for every `(start_time,
+    /// end_time)` pair, but the slots are scanned once for all the ranges, e.g. to compute the
+    /// counts of several dashboard windows together. All ranges are evaluated against the same
+    /// latest observed time.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(70);
+    /// counter.register(100);
+    ///
+    /// assert_eq!(counter.count_in_many(&[(96, 112), (64, 112), (0, 112)]), vec![1, 2, 3]);
+    /// ```
+    pub fn count_in_many(&self, ranges: &[(u64, u64)]) -> Vec<u32> {
+        let orderings = &self.orderings().count_in;
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        let valid_ranges = ranges
+            .iter()
+            .map(|&(start_time, end_time)| {
+                if start_time >= end_time {
+                    return 0..0;
+                }
+                valid_range(
+                    current_max_time,
+                    self.slot_size_exp,
+                    self.saturating_window_size(),
+                    start_time,
+                    end_time,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if valid_ranges.iter().all(Range::is_empty) {
+            return vec![0; ranges.len()];
+        }
+
+        let mut counts = vec![0u64; ranges.len()];
+        for slot in &self.slots {
+            let (time_in_slot, slot_count) = slot.load(orderings);
+            for (count, valid_range) in counts.iter_mut().zip(&valid_ranges) {
+                if valid_range.contains(&time_in_slot) {
+                    *count += slot_count as u64;
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|count| u32::try_from(count).unwrap_or(u32::MAX))
+            .collect()
+    }
+
+    /// Returns the total number of invocations within the specified time range, rounding
+    /// bounds that fall inside a slot according to `mode`.
+    ///
+    /// A slot's invocations can't be split, so a query whose bounds fall inside slots either
+    /// counts those partial slots in full or leaves them out. [`RoundMode::Outward`] counts
+    /// them, exactly like [`count_in`](Self::count_in): `[10, 12)` with 16-unit slots counts
+    /// all of `[0, 16)`. [`RoundMode::Inward`] leaves them out, so only invocations certainly
+    /// within the range are counted: the same query counts nothing. The two modes bracket the
+    /// exact count. Bounds on slot boundaries are kept as they are in both modes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{InvocationCounter, RoundMode};
+    /// let counter = InvocationCounter::new(3, 4); // 16 time units per slot
+    ///
+    /// counter.register(5);
+    /// counter.register(20);
+    /// counter.register(40);
+    ///
+    /// assert_eq!(counter.count_in_rounded(10, 36, RoundMode::Outward), 3); // [0, 48)
+    /// assert_eq!(counter.count_in_rounded(10, 36, RoundMode::Inward), 1);  // [16, 32)
+    /// assert_eq!(counter.count_in_rounded(10, 12, RoundMode::Inward), 0);
+    /// ```
+    pub fn count_in_rounded(&self, start_time: u64, end_time: u64, mode: RoundMode) -> u32 {
+        match mode {
+            RoundMode::Outward => self.count_in(start_time, end_time),
+            RoundMode::Inward => {
+                let slot_mask = (1 << self.slot_size_exp) - 1;
+                let start_time = if start_time & slot_mask == 0 {
+                    start_time
+                } else {
+                    (start_time & !slot_mask).saturating_add(slot_mask + 1)
+                };
+                self.count_in(start_time, end_time & !slot_mask)
+            }
+        }
+    }
+
+    /// Returns the total number of invocations within the specified time range, with bounds
+    /// of any integer type.
+    ///
+    /// This is [`count_in`](Self::count_in) after converting both bounds to `u64`. Bounds that
+    /// don't fit are clamped: negative ones to `0`, and ones above `u64::MAX` to `u64::MAX`.
+    /// See [`register_t`](Self::register_t) for an example.
+    pub fn count_in_t<T>(&self, start_time: T, end_time: T) -> u32
+    where
+        T: TryInto<u64> + PartialOrd + Default + Copy,
+    {
+        let to_time = |time: T| {
+            time.try_into()
+                .unwrap_or(if time < T::default() { 0 } else { u64::MAX })
+        };
+
+        self.count_in(to_time(start_time), to_time(end_time))
+    }
+
+    /// Returns the total number of invocations within `range`.
+    ///
+    /// This is [`count_in`](Self::count_in) with the bounds given as a Rust range, so
+    /// `count_range(10..50)`, `count_range(10..=49)` and `count_in(10, 50)` are the same query.
+    /// An unbounded start means `0` and an unbounded end means just after the latest observed
+    /// time, so `count_range(..)` counts the whole window.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(25);
+    /// counter.register(100);
+    ///
+    /// assert_eq!(counter.count_range(0..50), 2);
+    /// assert_eq!(counter.count_range(16..=31), 1);
+    /// assert_eq!(counter.count_range(20..), 2);
+    /// assert_eq!(counter.count_range(..), 3);
+    /// ```
+    pub fn count_range<R: RangeBounds<u64>>(&self, range: R) -> u32 {
+        let start_time = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end_time = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self
+                .max_current_time
+                .load(self.orderings().count_in.max_time_load)
+                .saturating_add(1),
+        };
+
+        self.count_in(start_time, end_time)
+    }
+
+    /// Returns the total number of invocations within the specified time range, as a `u64`.
+    ///
+    /// Every slot holds up to `u32::MAX` invocations, so the sum over several slots can exceed
+    /// what [`count_in`](Self::count_in) can return, in which case `count_in` saturates at
+    /// `u32::MAX`. This method accumulates into a `u64` instead and is exact for any range;
+    /// prefer it for counters with many slots under heavy load.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_n(10, u32::MAX);
+    /// counter.register_n(20, 10);
+    ///
+    /// assert_eq!(counter.count_in_u64(0, 32), u32::MAX as u64 + 10);
+    /// assert_eq!(counter.count_in(0, 32), u32::MAX);
+    /// ```
+    pub fn count_in_u64(&self, start_time: u64, end_time: u64) -> u64 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let valid_range = self.valid_range(start_time, end_time);
+        if valid_range.is_empty() {
+            return 0;
+        }
+
+        #[cfg(feature = "simd")]
+        {
+            if !self.prefers_indexed_scan(&valid_range) {
+                return self.sum_full_simd(valid_range);
+            }
+        }
+
+        let mut count = 0;
+        self.scan_valid_range(valid_range, |_, slot_count| count += slot_count as u64);
+
+        count
+    }
+
+    /// Returns the average number of invocations per time unit within the specified time
+    /// range.
+    ///
+    /// This is [`count_in_u64`](Self::count_in_u64) divided by `end_time - start_time`, which
+    /// makes ranges of different lengths comparable. The divisor is the requested length, not
+    /// the slot-aligned one the count is taken over: for ranges that don't fall on slot
+    /// boundaries the rate is approximate, like the count itself. Returns `0.0` if
+    /// `start_time >= end_time`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register_n(10, 8);
+    /// counter.register_n(20, 24);
+    ///
+    /// assert_eq!(counter.rate_in(0, 16), 0.5);
+    /// assert_eq!(counter.rate_in(0, 32), 1.0);
+    /// assert_eq!(counter.rate_in(32, 32), 0.0);
+    /// ```
+    pub fn rate_in(&self, start_time: u64, end_time: u64) -> f64 {
+        if start_time >= end_time {
+            return 0.0;
+        }
+
+        self.count_in_u64(start_time, end_time) as f64 / (end_time - start_time) as f64
+    }
+
+    /// Returns the number of invocations within the specified time range, and whether that
+    /// number is only a lower bound.
+    ///
+    /// This behaves like [`count_in`](Self::count_in), but accumulates into a `u64` and also
+    /// reports whether any of the summed slots was at `u32::MAX`. A slot at that value may have
+    /// saturated, in which case further invocations in its interval were not recorded and the
+    /// returned sum is a floor rather than an exact value.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - The start time of the range to query (inclusive)
+    /// * `end_time` - The end time of the range to query (exclusive)
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the total number of invocations in the range and a flag that is `true` if
+    /// any slot in the range was saturated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(10);
+    /// counter.register(25);
+    ///
+    /// assert_eq!(counter.count_in_saturating(0, 50), (2, false));
+    /// ```
+    pub fn count_in_saturating(&self, start_time: u64, end_time: u64) -> (u64, bool) {
+        if start_time >= end_time {
+            return (0, false);
+        }
+
+        let mut count = 0;
+        let mut saturated = false;
+        self.scan_in(start_time, end_time, |_, slot_count| {
+            saturated |= slot_count == u32::MAX;
+            count += slot_count as u64;
+        });
+
+        (count, saturated)
+    }
+
+    /// Returns the number of invocations within the specified time range, collecting the
+    /// summed slots into a caller-provided buffer.
+    ///
+    /// `scratch` is cleared, then filled with the `(interval_start, count)` pair of every
+    /// non-empty slot counted by the query, in no particular order, so callers can inspect the
+    /// breakdown after the call. The buffer is grown to hold every slot of the counter on first
+    /// use, so reusing the same buffer across calls on the same counter never allocates again.
+    /// This suits high-frequency query loops.
+    ///
+    /// The range is aligned to slot boundaries exactly like [`count_in`](Self::count_in), and
+    /// the count is accumulated into a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// let mut scratch = Vec::new();
+    ///
+    /// counter.register(10);
+    /// counter.register(25);
+    /// counter.register(26);
+    ///
+    /// assert_eq!(counter.count_in_with_scratch(0, 50, &mut scratch), 3);
+    ///
+    /// scratch.sort_unstable();
+    /// assert_eq!(scratch, vec![(0, 1), (16, 2)]);
+    /// ```
+    pub fn count_in_with_scratch(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        scratch: &mut Vec<(u64, u32)>,
+    ) -> u64 {
+        scratch.clear();
+        scratch.reserve(self.slots.len());
+        if start_time >= end_time {
+            return 0;
+        }
+
+        self.scan_in(start_time, end_time, |interval_start, slot_count| {
+            if slot_count > 0 {
+                scratch.push((interval_start, slot_count));
+            }
+        });
+
+        scratch.iter().map(|&(_, count)| count as u64).sum()
+    }
+
+    /// Returns the number of invocations from `start_time` up to, but excluding, the interval
+    /// containing `now`.
+    ///
+    /// The interval containing `now` is still in progress, so including it would understate
+    /// rates computed over the result. This queries `[start_time, interval_start_of(now))`, i.e.
+    /// only fully elapsed intervals, with the same alignment rules as
+    /// [`count_in`](Self::count_in), accumulating into a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units
+    ///
+    /// counter.register(10);
+    /// counter.register(20);
+    /// counter.register(40); // In the current interval [32, 48)
+    ///
+    /// assert_eq!(counter.count_in_complete(0, 45), 2);
+    /// ```
+    pub fn count_in_complete(&self, start_time: u64, now: u64) -> u64 {
+        let current_interval_start = now >> self.slot_size_exp << self.slot_size_exp;
+
+        self.count_in_u64(start_time, current_interval_start)
+    }
+
+    /// Returns the number of invocations between two wall-clock instants.
+    ///
+    /// `start` and `end` are converted to ticks of `unit` elapsed since `epoch`, which must be
+    /// the same epoch and unit used to produce the timestamps passed to `register`. The query
+    /// is then answered like [`count_in`](Self::count_in), accumulating into a `u64`.
+    ///
+    /// Conversions round down to whole ticks. Times before `epoch` are clamped to tick `0`,
+    /// and tick values that don't fit in a `u64` are clamped to `u64::MAX`.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `unit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// # use std::time::{Duration, SystemTime};
+    /// let epoch = SystemTime::UNIX_EPOCH;
+    /// let unit = Duration::from_secs(1);
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 seconds
+    ///
+    /// counter.register(100); // 100 seconds after the epoch
+    ///
+    /// let start = epoch + Duration::from_secs(96);
+    /// let end = epoch + Duration::from_secs(112);
+    /// assert_eq!(counter.count_in_system(start, end, epoch, unit), 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn count_in_system(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+        epoch: SystemTime,
+        unit: Duration,
+    ) -> u64 {
+        assert!(!unit.is_zero(), "unit must be a non-zero duration");
+
+        let to_ticks = |time: SystemTime| {
+            let elapsed = time.duration_since(epoch).unwrap_or(Duration::ZERO);
+            u64::try_from(elapsed.as_nanos() / unit.as_nanos()).unwrap_or(u64::MAX)
+        };
+
+        self.count_in_u64(to_ticks(start), to_ticks(end))
+    }
+
+    /// Returns the number of invocations within `(start_time, end_time]`, with exclusive start
+    /// and inclusive end, as used by billing periods.
+    ///
+    /// Intervals can't be split, so each one is attributed to the billing window that contains
+    /// its **last** time unit: an interval is counted if `start_time < last <= end_time`. In
+    /// particular, the interval `start_time` falls in is only counted if it extends past
+    /// `start_time`, and the interval `end_time` falls in is only counted if `end_time` is its
+    /// last time unit. This guarantees that back-to-back windows `(a, b]` and `(b, c]` never
+    /// count the same interval twice and together count exactly what `(a, c]` counts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 2); // 8 slots × 4 units
+    ///
+    /// for time in 0..32 {
+    ///     counter.register(time);
+    /// }
+    ///
+    /// let first = counter.count_in_billing(0, 10);   // Intervals ending at 3 and 7
+    /// let second = counter.count_in_billing(10, 31); // Intervals ending at 11, 15, ..., 31
+    ///
+    /// assert_eq!(first, 8);
+    /// assert_eq!(second, 24);
+    /// assert_eq!(first + second, counter.count_in_billing(0, 31));
+    /// ```
+    pub fn count_in_billing(&self, start_time: u64, end_time: u64) -> u64 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let last_offset = (1 << self.slot_size_exp) - 1;
+
+        let mut count = 0;
+        self.scan_in(
+            start_time,
+            end_time.saturating_add(1),
+            |interval_start, slot_count| {
+                let last = interval_start + last_offset;
+                if start_time < last && last <= end_time {
+                    count += slot_count as u64;
+                }
+            },
+        );
+
+        count
+    }
+
+    /// Returns the number of invocations within the specified time range, split into `groups`
+    /// consecutive sub-ranges.
+    ///
+    /// The range is first aligned to slot boundaries like [`count_in`](Self::count_in), then
+    /// its intervals are divided into `groups` consecutive groups and the count of each group
+    /// is returned, oldest first. This produces a small histogram of the range in a single
+    /// scan.
+    ///
+    /// # Remainder Handling
+    ///
+    /// When the number of intervals `n` in the aligned range isn't a multiple of `groups`,
+    /// groups hold either `⌊n / groups⌋` or `⌈n / groups⌉` intervals, with the larger groups
+    /// spread evenly across the range (interval `i` goes to group `i × groups / n`). If
+    /// `groups > n`, some groups contain no interval and are always `0`.
+    ///
+    /// Returns an empty `Vec` if `groups` is `0`, and `groups` zeros if the range is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 2); // 8 slots × 4 units = 32-unit window
+    ///
+    /// for time in [1, 2, 5, 13, 20, 21, 22, 30] {
+    ///     counter.register(time);
+    /// }
+    ///
+    /// // 8 intervals in 4 groups of 2 intervals (8 time units) each
+    /// assert_eq!(counter.grouped_count_in(0, 32, 4), vec![3, 1, 3, 1]);
+    /// ```
+    pub fn grouped_count_in(&self, start_time: u64, end_time: u64, groups: usize) -> Vec<u64> {
+        let mut counts = vec![0; groups];
+        if groups == 0 || start_time >= end_time {
+            return counts;
+        }
+
+        // The aligned end may have saturated at `u64::MAX`, so count the last interval
+        // inclusively
+        let first_interval = start_time >> self.slot_size_exp;
+        let last_interval = (end_time - 1) >> self.slot_size_exp;
+        let interval_count = last_interval - first_interval + 1;
+
+        self.scan_in(start_time, end_time, |interval_start, slot_count| {
+            let interval = (interval_start >> self.slot_size_exp) - first_interval;
+            let group = interval as u128 * groups as u128 / interval_count as u128;
+            counts[(group as usize).min(groups - 1)] += slot_count as u64;
+        });
+
+        counts
+    }
+
+    /// Returns the number of invocations in each age tier relative to `now`.
+    ///
+    /// `tier_boundaries` are ages in time units, e.g. `[10, 60, 300]` for the tiers "last 10",
+    /// "10 to 60" and "60 to 300" units ago. Intervals can't be split, so each one is
+    /// attributed by the age of its start, `now - interval_start`: tier `i` counts the intervals
+    /// with `tier_boundaries[i - 1] < age <= tier_boundaries[i]`, and the first tier starts at
+    /// age `0`. Intervals starting after `now` or older than the last boundary aren't counted.
+    /// All tiers are computed in a single scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidQuery`] if `tier_boundaries` isn't strictly ascending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(4, 2); // 16 slots × 4 units = 64-unit window
+    ///
+    /// for time in [2, 30, 45, 58, 61] {
+    ///     counter.register(time);
+    /// }
+    ///
+    /// // Intervals starting at 60 and 56, then 44, then 28
+    /// assert_eq!(counter.tiered_counts(61, &[8, 20, 40]), Ok(vec![2, 1, 1]));
+    /// ```
+    pub fn tiered_counts(
+        &self,
+        now: u64,
+        tier_boundaries: &[u64],
+    ) -> Result<Vec<u64>, CounterError> {
+        if tier_boundaries.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(CounterError::InvalidQuery {
+                reason: "tier boundaries must be strictly ascending",
+            });
+        }
+
+        let mut counts = vec![0; tier_boundaries.len()];
+        let Some(&oldest) = tier_boundaries.last() else {
+            return Ok(counts);
+        };
+
+        self.scan_in(
+            now.saturating_sub(oldest),
+            now.saturating_add(1),
+            |interval_start, slot_count| {
+                if interval_start > now {
+                    return;
+                }
+                let age = now - interval_start;
+                let tier = tier_boundaries.partition_point(|&boundary| boundary < age);
+                if let Some(count) = counts.get_mut(tier) {
+                    *count += slot_count as u64;
+                }
+            },
+        );
+
+        Ok(counts)
+    }
+
+    /// Returns the number of distinct intervals within the specified time range that recorded
+    /// at least one invocation.
+    ///
+    /// This complements [`count_in`](Self::count_in) for burstiness analysis: 100 invocations in
+    /// a single burst yield 1 active interval, while 100 invocations spread over the range yield
+    /// many. The range is aligned to slot boundaries exactly like `count_in`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - The start time of the range to query (inclusive)
+    /// * `end_time` - The end time of the range to query (exclusive)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units = 128-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(12);
+    /// counter.register(40);
+    ///
+    /// assert_eq!(counter.count_in(0, 64), 3);
+    /// assert_eq!(counter.active_intervals_in(0, 64), 2); // [0, 16) and [32, 48)
+    /// ```
+    pub fn active_intervals_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let mut active = 0;
+        self.scan_in(start_time, end_time, |_, slot_count| {
+            if slot_count > 0 {
+                active += 1;
+            }
+        });
+
+        active
+    }
+
+    /// Returns the distribution of gaps between consecutive active intervals of the window.
+    ///
+    /// The non-empty intervals of the current window are sorted by time, and for each pair of
+    /// consecutive ones the gap between their starts, in time units, is recorded. The result
+    /// holds `(gap, occurrences)` pairs sorted by gap. Regular traffic produces a single gap
+    /// equal to the slot size, while bursty traffic produces a wide spread of gaps.
+    ///
+    /// Returns an empty `Vec` if fewer than two intervals are active.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(4, 2); // 16 slots × 4 units
+    ///
+    /// for time in [0, 4, 8, 20, 32] {
+    ///     counter.register(time);
+    /// }
+    ///
+    /// assert_eq!(counter.interarrival_histogram(), vec![(4, 2), (12, 2)]);
+    /// ```
+    pub fn interarrival_histogram(&self) -> Vec<(u64, u32)> {
+        let mut gaps = alloc::collections::BTreeMap::new();
+        for pair in self.snapshot().windows(2) {
+            *gaps.entry(pair[1].0 - pair[0].0).or_insert(0) += 1;
+        }
+
+        gaps.into_iter().collect()
+    }
+
+    /// Returns the `(interval_start, count)` pair of every non-empty interval of the current
+    /// window, sorted by `interval_start`.
+    ///
+    /// Empty slots and slots holding intervals that left the window are skipped, so this is a
+    /// compact histogram of the window, e.g. to render a sparkline. Each slot is read
+    /// consistently, but slots are read one after the other: under concurrent registration it
+    /// is a best-effort view rather than a consistent copy. A [`CounterSnapshot`] keeps a copy
+    /// that can be queried instead. [`to_buckets`](Self::to_buckets) also lists the empty
+    /// intervals.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units
+    ///
+    /// counter.register(40);
+    /// counter.register(10);
+    /// counter.register(12);
+    ///
+    /// assert_eq!(counter.snapshot(), vec![(0, 2), (32, 1)]);
+    /// ```
+    pub fn snapshot(&self) -> Vec<(u64, u32)> {
+        let ring_range = self.ring_range();
+
+        let mut intervals = Vec::new();
+        self.scan_in(
+            ring_range.start,
+            ring_range.end,
+            |interval_start, slot_count| {
+                if slot_count > 0 {
+                    intervals.push((interval_start, slot_count));
+                }
+            },
+        );
+        intervals.sort_unstable();
+
+        intervals
+    }
+
+    /// Returns a [`Bucket`] for every interval of the current window, sorted by start time.
+    ///
+    /// Unlike [`snapshot`](Self::snapshot), intervals without invocations are included as
+    /// zero-count buckets, so the result is a dense histogram of the window that can be plotted
+    /// as is. Before the window is full, it only covers the intervals from time `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{Bucket, InvocationCounter};
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units
+    ///
+    /// counter.register(10);
+    /// counter.register(40);
+    ///
+    /// assert_eq!(
+    ///     counter.to_buckets(),
+    ///     vec![
+    ///         Bucket { start: 0, end: 16, count: 1 },
+    ///         Bucket { start: 16, end: 32, count: 0 },
+    ///         Bucket { start: 32, end: 48, count: 1 },
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_buckets(&self) -> Vec<Bucket> {
+        let ring_range = self.ring_range();
+        if ring_range.is_empty() {
+            return Vec::new();
+        }
+
+        let slot_size = 1 << self.slot_size_exp;
+        // The start isn't aligned when the window end saturates at `u64::MAX`
+        let first_interval = ring_range.start.div_ceil(slot_size);
+        let last_interval = (ring_range.end - 1) >> self.slot_size_exp;
+        let mut buckets = (first_interval..=last_interval)
+            .map(|interval| {
+                let start = interval << self.slot_size_exp;
+                Bucket {
+                    start,
+                    end: start.saturating_add(slot_size),
+                    count: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.scan_in(
+            ring_range.start,
+            ring_range.end,
+            |interval_start, slot_count| {
+                let index = (interval_start >> self.slot_size_exp) - first_interval;
+                buckets[index as usize].count = slot_count;
+            },
+        );
+
+        buckets
+    }
+
+    /// Returns the interval of the current window with the most invocations, as
+    /// `(interval_start, count)`.
+    ///
+    /// Only intervals within the window are considered, like in [`count_in`](Self::count_in).
+    /// When several intervals share the highest count, the earliest one is returned. Returns
+    /// `None` if no interval of the window recorded any invocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4); // 8 slots × 16 units
+    /// assert_eq!(counter.peak_interval(), None);
+    ///
+    /// counter.register_n(10, 3);
+    /// counter.register_n(40, 7);
+    /// counter.register_n(90, 7);
+    ///
+    /// assert_eq!(counter.peak_interval(), Some((32, 7)));
+    /// ```
+    pub fn peak_interval(&self) -> Option<(u64, u32)> {
+        let ring_range = self.ring_range();
+
+        let mut peak: Option<(u64, u32)> = None;
+        self.scan_in(
+            ring_range.start,
+            ring_range.end,
+            |interval_start, slot_count| {
+                let is_peak = match peak {
+                    None => slot_count > 0,
+                    Some((peak_start, peak_count)) => {
+                        slot_count > peak_count
+                            || (slot_count == peak_count && interval_start < peak_start)
+                    }
+                };
+                if is_peak {
+                    peak = Some((interval_start, slot_count));
+                }
+            },
+        );
+
+        peak
+    }
+
+    /// Calls `f` with the `interval_start` and counter of every slot that a query over
+    /// `[start_time, end_time)` should include.
+    ///
+    /// Picks the cheaper of two equivalent strategies: ranges spanning fewer than a quarter of
+    /// the slots visit only the slots their intervals map to, wider ranges scan every slot.
+    /// Visiting one interval costs about three times as much as checking one slot in a scan.
+    fn scan_in(&self, start_time: u64, end_time: u64, f: impl FnMut(u64, u32)) {
+        let valid_range = self.valid_range(start_time, end_time);
+        if !valid_range.is_empty() {
+            self.scan_valid_range(valid_range, f);
+        }
+    }
+
+    /// Calls `f` with the `interval_start` and counter of every slot whose `interval_start`
+    /// is in the non-empty `valid_range`, picking the cheaper scan like
+    /// [`scan_in`](Self::scan_in).
+    fn scan_valid_range(&self, valid_range: Range<u64>, f: impl FnMut(u64, u32)) {
+        if self.prefers_indexed_scan(&valid_range) {
+            self.scan_indexed(valid_range, f);
+        } else {
+            self.scan_full(valid_range, f);
+        }
+    }
+
+    /// Returns `true` if [`scan_indexed`](Self::scan_indexed) is cheaper than
+    /// [`scan_full`](Self::scan_full) over the non-empty `valid_range`.
+    fn prefers_indexed_scan(&self, valid_range: &Range<u64>) -> bool {
+        // The end may have saturated at `u64::MAX`, so count the last interval inclusively
+        let first_interval = valid_range.start >> self.slot_size_exp;
+        let last_interval = (valid_range.end - 1) >> self.slot_size_exp;
+        let interval_count = last_interval - first_interval + 1;
+        interval_count < (self.slots.len() / 4) as u64
+    }
+
+    /// Calls `f` for every slot whose `interval_start` is in `valid_range`, checking every
+    /// slot.
+    fn scan_full(&self, valid_range: Range<u64>, mut f: impl FnMut(u64, u32)) {
+        let orderings = &self.orderings().count_in;
+        for slot in &self.slots {
+            let (time_in_slot, count) = slot.load(orderings);
+            if valid_range.contains(&time_in_slot) {
+                f(time_in_slot, count);
+            }
+        }
+    }
+
+    /// Calls `f` for every slot whose `interval_start` is in `valid_range`, only checking the
+    /// slot each interval of the range maps to.
+    ///
+    /// `valid_range` must be non-empty, within the window, so that it spans at most one lap of
+    /// the ring, and slot-aligned, except for an end saturated at `u64::MAX`.
+    fn scan_indexed(&self, valid_range: Range<u64>, mut f: impl FnMut(u64, u32)) {
+        let orderings = &self.orderings().count_in;
+        let first_interval = valid_range.start >> self.slot_size_exp;
+        let last_interval = (valid_range.end - 1) >> self.slot_size_exp;
+        for interval in first_interval..=last_interval {
+            let slot = &self.slots[(interval & self.slot_count_mask) as usize];
+            let interval_start = interval << self.slot_size_exp;
+            let (time_in_slot, count) = slot.load(orderings);
+            if time_in_slot == interval_start {
+                f(interval_start, count);
+            }
+        }
+    }
+
+    /// Returns the range of `interval_start` values that a query over
+    /// `[start_time, end_time)` should sum.
+    fn valid_range(&self, start_time: u64, end_time: u64) -> Range<u64> {
+        let current_max_time = self
+            .max_current_time
+            .load(self.orderings().count_in.max_time_load);
+        valid_range(
+            current_max_time,
+            self.slot_size_exp,
+            self.saturating_window_size(),
+            start_time,
+            end_time,
+        )
+    }
+
+    /// Returns `true` if the interval starting at `interval_start` is within the current
+    /// window.
+    ///
+    /// This is the test every query applies to a slot before counting it: the window covers
+    /// the `2^slot_count_exp` intervals ending with the one that contains the latest observed
+    /// time. An interval outside the window holds stale data, even if a slot still tracks it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(2, 4); // 4 slots × 16 units = 64-unit window
+    ///
+    /// counter.register(100); // The window is now [48, 112)
+    ///
+    /// assert!(counter.is_in_window(48));
+    /// assert!(counter.is_in_window(96));
+    /// assert!(!counter.is_in_window(32));
+    /// assert!(!counter.is_in_window(112));
+    /// ```
+    pub fn is_in_window(&self, interval_start: u64) -> bool {
+        self.ring_range().contains(&interval_start)
+    }
+
+    /// Returns the `(start, end)` bounds of the current window: the range of time that queries
+    /// can answer for, `start` inclusive and `end` exclusive.
+    ///
+    /// These are the bounds every query intersects its range with, computed from the latest
+    /// observed time (see [`max_time`](Self::max_time)): the window ends at the end of the
+    /// interval containing it, and spans `2^slot_count_exp` intervals, or starts at `0` early
+    /// in the counter's life. `end` saturates at `u64::MAX`. A query starting before `start`
+    /// asks for data that was already evicted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(2, 4); // 4 slots × 16 units = 64-unit window
+    ///
+    /// counter.register(100);
+    /// assert_eq!(counter.window_bounds(), (48, 112));
+    ///
+    /// let (start, _) = counter.window_bounds();
+    /// let query_start = 20;
+    /// assert!(query_start < start); // [20, 48) was evicted
+    /// ```
+    pub fn window_bounds(&self) -> (u64, u64) {
+        let ring_range = self.ring_range();
+        (ring_range.start, ring_range.end)
+    }
+
+    /// Returns the range of `interval_start` values currently held by the ring buffer.
+    fn ring_range(&self) -> Range<u64> {
+        let current_max_time = self
+            .max_current_time
+            .load(self.orderings().count_in.max_time_load);
+        ring_range(
+            current_max_time,
+            self.slot_size_exp,
+            self.saturating_window_size(),
+        )
+    }
+
+    /// Changes the time resolution of this counter, keeping its data, and returns the counter
+    /// as it was before the change.
+    ///
+    /// A new counter with the same number of slots and 2^`new_slot_size_exp` time units per
+    /// slot is built, and every non-empty slot of the current window is re-registered into it at
+    /// its `interval_start`, oldest first. `self` is then replaced by the new counter. The
+    /// latest observed time, the lifetime total and the spillover are carried over.
+    ///
+    /// # Approximation
+    ///
+    /// Going coarser merges adjacent intervals and preserves totals exactly. Going finer can't
+    /// split an interval's count, so all of it is placed in the first finer interval; since
+    /// the window shrinks with the slot size, data older than the new window is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_slot_size_exp` is rejected by [`try_new`](Self::try_new), i.e. if it is 64
+    /// or more. The counter is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let mut counter = InvocationCounter::new(3, 2); // 8 slots × 4 units = 32-unit window
+    ///
+    /// counter.register(1);
+    /// counter.register(5);
+    ///
+    /// let previous = counter.replace_slot_size(3); // 8 slots × 8 units = 64-unit window
+    ///
+    /// assert_eq!(counter.slot_size_exp(), 3);
+    /// assert_eq!(counter.count_in(0, 8), 2); // Both intervals merged into [0, 8)
+    /// assert_eq!(previous.count_in(0, 4), 1);
+    /// ```
+    pub fn replace_slot_size(&mut self, new_slot_size_exp: u8) -> Self {
+        let mut replacement = InvocationCounter::new(self.slot_count_exp, new_slot_size_exp);
+
+        let mut intervals = self
+            .slots
+            .iter()
+            .map(|slot| slot.load(&self.orderings().count_in))
+            .filter(|&(interval_start, count)| count > 0 && self.is_in_window(interval_start))
+            .collect::<Vec<_>>();
+        intervals.sort_unstable();
+
+        for (interval_start, count) in intervals {
+            replacement.register_n_observed(interval_start, count);
+        }
+
+        replacement.copy_settings_from(self);
+        replacement.advance_time(self.max_current_time.load(Ordering::Acquire));
+        replacement
+            .lifetime_total
+            .store(self.lifetime_count(), Ordering::Relaxed);
+        replacement
+            .spillover
+            .store(self.spillover(), Ordering::Relaxed);
+
+        core::mem::replace(self, replacement)
+    }
+
+    /// Removes all recorded invocations from the window.
+    ///
+    /// Every slot is zeroed and the latest observed time goes back to `0`, so the window
+    /// behaves as if nothing had ever been registered. The spillover is cleared with the
+    /// slots it overflowed from, while the [`lifetime_count`](Self::lifetime_count) is kept:
+    /// use [`reset_lifetime`](Self::reset_lifetime) to clear it as well.
+    ///
+    /// This is safe to call while other threads register, but registrations running
+    /// concurrently with `clear` may be lost, or may survive it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    /// counter.register(10);
+    ///
+    /// counter.clear();
+    ///
+    /// assert_eq!(counter.count_in(0, 16), 0);
+    /// assert_eq!(counter.lifetime_count(), 1);
+    /// ```
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.swap(&self.orderings().register, Slot::EMPTY, 0);
+        }
+        self.max_current_time.store(0, Ordering::Release);
+        self.spillover.store(0, Ordering::Relaxed);
+        self.bump_generation();
+    }
+
+    /// Resets every slot and the latest observed time to `0`, without reallocating.
+    ///
+    /// This is the same as [`clear`](Self::clear), for harnesses that reuse one counter across
+    /// runs. It is safe to call while other threads register, but registrations running
+    /// concurrently with the reset may be lost, and queries may observe a partially reset
+    /// counter until it returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// for run in 0..3 {
+    ///     counter.register(10);
+    ///     assert_eq!(counter.count(), 1);
+    ///     counter.reset();
+    /// }
+    /// ```
+    pub fn reset(&self) {
+        self.clear();
+    }
+
+    /// Eagerly forgets the intervals that end at or before the slot boundary of `cutoff`.
+    ///
+    /// Every slot tracking an interval that starts before `cutoff` aligned down to a slot
+    /// boundary is emptied, so no query counts it anymore, even before its slot would be
+    /// reused. The interval containing `cutoff` and newer ones are kept. The latest observed
+    /// time, the lifetime total and the spillover are left untouched.
+    ///
+    /// This is safe to call while other threads register: each slot is emptied under
+    /// its rollover lock, so a slot that was concurrently rolled over to a newer interval is
+    /// left alone. A registration racing with the reset of its own slot may be lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(3, 4);
+    ///
+    /// counter.register(10);
+    /// counter.register(20);
+    /// counter.register(40);
+    ///
+    /// counter.reset_before(35); // Forgets [0, 16) and [16, 32)
+    ///
+    /// assert_eq!(counter.count_in(0, 128), 1);
+    /// ```
+    pub fn reset_before(&self, cutoff: u64) {
+        let cutoff = cutoff >> self.slot_size_exp << self.slot_size_exp;
+
+        for slot in &self.slots {
+            let time_in_slot = slot
+                .interval_start
+                .load(self.orderings().register.slot_interval_load);
+            if time_in_slot == Slot::EMPTY || time_in_slot >= cutoff {
+                continue;
+            }
+            slot.replace(&self.orderings().register, |time_in_slot| {
+                (time_in_slot != Slot::EMPTY && time_in_slot < cutoff).then_some((Slot::EMPTY, 0))
+            });
+        }
+        self.bump_generation();
+    }
+
+    /// Clears this counter and reconfigures it to a new geometry, reusing its slot allocation
+    /// when possible.
+    ///
+    /// If `slot_count_exp` is unchanged, the existing slots are zeroed in place and only the
+    /// slot size is updated; otherwise a new slot array is allocated. In both cases the counter
+    /// ends up in the same state as `InvocationCounter::new(slot_count_exp, slot_size_exp)`,
+    /// including a zero [`lifetime_count`](Self::lifetime_count) and
+    /// [`spillover`](Self::spillover); only settings such as the
+    /// [registration cap](Self::with_max_advance_per_register) and the [pause](Self::pause)
+    /// state are kept. This makes counters cheap to recycle through a pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected by [`try_reset_for`](Self::try_reset_for). The
+    /// counter is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let mut counter = InvocationCounter::new(3, 4);
+    /// counter.register(10);
+    ///
+    /// counter.reset_for(3, 6); // Same slot count: no reallocation
+    ///
+    /// assert_eq!(counter.slot_size_exp(), 6);
+    /// assert_eq!(counter.count_in(0, 64), 0);
+    /// ```
+    pub fn reset_for(&mut self, slot_count_exp: u8, slot_size_exp: u8) {
+        self.try_reset_for(slot_count_exp, slot_size_exp)
+            .unwrap_or_else(|error| {
+                panic!("InvocationCounter::reset_for({slot_count_exp}, {slot_size_exp}): {error}")
+            })
+    }
+
+    /// Clears this counter and reconfigures it to a new geometry like
+    /// [`reset_for`](Self::reset_for), returning an error instead of panicking if the
+    /// configuration is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::InvalidConfig`] if either exponent is out of range, as
+    /// [`try_new`](Self::try_new) does. The counter is then left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let mut counter = InvocationCounter::new(3, 4);
+    /// counter.register(10);
+    ///
+    /// assert!(counter.try_reset_for(3, 64).is_err());
+    /// assert_eq!(counter.count_in(0, 16), 1); // Untouched
+    /// ```
+    pub fn try_reset_for(
+        &mut self,
+        slot_count_exp: u8,
+        slot_size_exp: u8,
+    ) -> Result<(), CounterError> {
+        check_geometry(slot_count_exp, slot_size_exp)?;
+
+        if self.slot_count_exp != slot_count_exp {
+            let mut replacement = Self::new(slot_count_exp, slot_size_exp);
+            replacement.copy_settings_from(self);
+            *self = replacement;
+            return Ok(());
+        }
+
+        for slot in self.slots.iter_mut() {
+            *slot.interval_start.get_mut() = Slot::EMPTY;
+            *slot.counter.get_mut() = 0;
+        }
+        self.slot_size_exp = slot_size_exp;
+        *self.max_current_time.get_mut() = 0;
+        *self.lifetime_total.get_mut() = 0;
+        *self.spillover.get_mut() = 0;
+        *self.generation.get_mut() += 1;
+        #[cfg(feature = "telemetry")]
+        {
+            *self.out_of_order_count.get_mut() = 0;
+            *self.reset_count.get_mut() = 0;
+            *self.increment_count.get_mut() = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Exchanges the contents of this counter with `other`.
+    ///
+    /// Every slot, the latest observed time, the [lifetime count](Self::lifetime_count) and the
+    /// [spillover](Self::spillover) are swapped, so after the call `self` holds what `other`
+    /// held and vice versa. Settings and telemetry stay with their counter. No memory is
+    /// reallocated, which makes this suitable for a double-buffering (ping-pong) pattern:
+    /// accumulate into one counter while reading from the other, then swap.
+    ///
+    /// # Consistency
+    ///
+    /// The swap is performed slot by slot, so it is **not** atomic as a whole: a concurrent
+    /// `register` or `count_in` on either counter may observe a mix of old and new slots while
+    /// the swap is in progress. Each slot of `other` is emptied first and `self`'s content is
+    /// then merged into it, so a registration landing in `other` mid-swap is kept there, unless
+    /// it belongs to an interval older than the one moved in, which would have evicted it
+    /// anyway. Likewise, a concurrent advance of `other`'s latest observed time is never undone.
+    /// Once the call returns and no other thread is writing, both counters are fully exchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::GeometryMismatch`] if the two counters don't share the same
+    /// `slot_count_exp` and `slot_size_exp`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let active = InvocationCounter::new(3, 4);
+    /// let standby = InvocationCounter::new(3, 4);
+    ///
+    /// active.register(10);
+    /// active.swap_contents(&standby).unwrap();
+    ///
+    /// assert_eq!(active.count_in(0, 16), 0);
+    /// assert_eq!(standby.count_in(0, 16), 1);
+    /// ```
+    pub fn swap_contents(&self, other: &InvocationCounter) -> Result<(), CounterError> {
+        self.check_same_geometry(other)?;
+
+        if core::ptr::eq(self, other) {
+            return Ok(());
+        }
+
+        let mut overflow = 0;
+        for (mine, theirs) in self.slots.iter().zip(other.slots.iter()) {
+            let (their_start, their_counter) =
+                theirs.swap(&other.orderings().register, Slot::EMPTY, 0);
+            let (my_start, my_counter) =
+                mine.swap(&self.orderings().register, their_start, their_counter);
+            // Merge rather than store, so registrations made since `theirs` was emptied survive
+            if my_start != Slot::EMPTY {
+                overflow += theirs.merge(&other.orderings().register, my_start, my_counter) as u64;
+            }
+        }
+
+        // Each value is taken out of `other` first and added back, for the same reason
+        let their_max = other.max_current_time.swap(0, Ordering::AcqRel);
+        let my_max = self.max_current_time.swap(their_max, Ordering::AcqRel);
+        other.max_current_time.fetch_max(my_max, Ordering::AcqRel);
+
+        let their_total = other.lifetime_total.swap(0, Ordering::Relaxed);
+        let my_total = self.lifetime_total.swap(their_total, Ordering::Relaxed);
+        other.lifetime_total.fetch_add(my_total, Ordering::Relaxed);
+
+        let their_spillover = other.spillover.swap(0, Ordering::Relaxed);
+        let my_spillover = self.spillover.swap(their_spillover, Ordering::Relaxed);
+        other
+            .spillover
+            .fetch_add(my_spillover + overflow, Ordering::Relaxed);
+
+        self.bump_generation();
+        other.bump_generation();
+
+        Ok(())
+    }
+
+    /// Adds the invocations recorded by `other` into this counter.
+    ///
+    /// This combines counters sharded across threads or cores into a single view. Slot by
+    /// slot, `other`'s invocations are added when both counters track the same interval, and
+    /// replace this counter's slot when `other`'s interval is newer. When this counter's
+    /// interval is newer, `other`'s invocations in that slot are dropped: they would have been
+    /// evicted had they been registered here. The latest observed time becomes the latest of
+    /// both, and `other`'s [lifetime count](Self::lifetime_count) and
+    /// [spillover](Self::spillover) are added to this counter's. `other` is left unchanged.
+    ///
+    /// This is safe to call while other threads register, but a registration racing with the
+    /// merge of its slot may be lost, and queries may observe a partially merged counter until
+    /// the call returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::GeometryMismatch`] if the two counters don't share the same
+    /// `slot_count_exp` and `slot_size_exp`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let shard_a = InvocationCounter::new(3, 4);
+    /// let shard_b = InvocationCounter::new(3, 4);
+    ///
+    /// shard_a.register(10);
+    /// shard_b.register(12);
+    /// shard_b.register(40);
+    ///
+    /// shard_a.merge(&shard_b).unwrap();
+    /// assert_eq!(shard_a.count_in(0, 16), 2);
+    /// assert_eq!(shard_a.count(), 3);
+    /// ```
+    pub fn merge(&self, other: &InvocationCounter) -> Result<(), CounterError> {
+        self.check_same_geometry(other)?;
+
+        let mut overflow = 0;
+        for (mine, theirs) in self.slots.iter().zip(other.slots.iter()) {
+            let (their_start, their_counter) = theirs.load(&other.orderings().count_in);
+            if their_start != Slot::EMPTY && their_counter > 0 {
+                overflow +=
+                    mine.merge(&self.orderings().register, their_start, their_counter) as u64;
+            }
+        }
+
+        self.max_current_time.fetch_max(
+            other.max_current_time.load(Ordering::Acquire),
+            Ordering::AcqRel,
+        );
+        self.lifetime_total
+            .fetch_add(other.lifetime_count(), Ordering::Relaxed);
+        self.spillover
+            .fetch_add(other.spillover() + overflow, Ordering::Relaxed);
+        self.bump_generation();
+
+        Ok(())
+    }
+
+    /// Returns [`CounterError::GeometryMismatch`] unless `other` has the same slot count and
+    /// slot size as this counter.
+    fn check_same_geometry(&self, other: &InvocationCounter) -> Result<(), CounterError> {
+        if self.slot_count_exp != other.slot_count_exp || self.slot_size_exp != other.slot_size_exp
+        {
+            return Err(CounterError::GeometryMismatch {
+                expected: (self.slot_count_exp, self.slot_size_exp),
+                found: (other.slot_count_exp, other.slot_size_exp),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a general-purpose counter: `InvocationCounter::new(6, 10)`.
+///
+/// That is 64 slots of 1024 time units each, a 65,536-unit window: with millisecond
+/// timestamps, about a minute tracked at a one-second resolution. Use
+/// [`new`](InvocationCounter::new) to pick a geometry suited to your time unit and window.
+///
+/// # Examples
+///
+/// ```rust
+/// # use invocation_counter::InvocationCounter;
+/// let counter = InvocationCounter::default();
+///
+/// assert_eq!(counter.num_slots(), 64);
+/// assert_eq!(counter.slot_size(), 1024);
+/// assert_eq!(counter.window_size(), 65_536);
+/// ```
+impl Default for InvocationCounter {
+    fn default() -> Self {
+        Self::new(6, 10)
+    }
+}
+
+impl Clone for InvocationCounter {
+    /// Returns an independent copy of this counter: slots, latest observed time, totals and
+    /// settings.
+    ///
+    /// Every value is copied with a separate atomic load (each slot's interval and count are
+    /// read as a consistent pair), so cloning a counter while other threads register into it
+    /// gives a best-effort snapshot rather than a consistent transactional copy: registrations
+    /// racing with the clone may be partially reflected.
+    fn clone(&self) -> Self {
+        let load = |value: &AtomicU64| AtomicU64::new(value.load(Ordering::Acquire));
+
+        Self {
+            slots: self
+                .slots
+                .iter()
+                .map(|slot| {
+                    let (interval_start, count) = slot.load(&self.orderings().count_in);
+                    Slot::with_content(interval_start, count)
+                })
+                .collect(),
+            slot_count_exp: self.slot_count_exp,
+            slot_size_exp: self.slot_size_exp,
+            slot_count_mask: self.slot_count_mask,
+            max_current_time: load(&self.max_current_time),
+            lifetime_total: load(&self.lifetime_total),
+            spillover: load(&self.spillover),
+            max_advance_per_register: self.max_advance_per_register,
+            paused: AtomicBool::new(self.paused.load(Ordering::Acquire)),
+            advance_time_while_paused: self.advance_time_while_paused,
+            ordering_profile: self.ordering_profile,
+            generation: load(&self.generation),
+            #[cfg(feature = "telemetry")]
+            out_of_order_count: load(&self.out_of_order_count),
+            #[cfg(feature = "telemetry")]
+            reset_count: load(&self.reset_count),
+            #[cfg(feature = "telemetry")]
+            increment_count: load(&self.increment_count),
+        }
+    }
+}
+
+/// Two counters are equal if they have the same geometry, the same latest observed time and
+/// the same `(interval_start, counter)` in every slot.
+///
+/// Totals, settings and the generation aren't compared, so a counter equals its
+/// [snapshot](CounterSnapshot) restored with [`CounterSnapshot::restore`]. Like
+/// [`Clone`], the comparison loads every value separately: it is a best-effort snapshot
+/// comparison, and isn't meaningful while either counter is being mutated.
+///
+/// # Examples
+///
+/// ```rust
+/// # use invocation_counter::{CounterSnapshot, InvocationCounter};
+/// let counter = InvocationCounter::new(3, 4);
+/// counter.register(10);
+///
+/// let restored = CounterSnapshot::capture(&counter).restore().unwrap();
+/// assert_eq!(restored, counter);
+///
+/// counter.register(20);
+/// assert_ne!(restored, counter);
+/// ```
+impl PartialEq for InvocationCounter {
+    fn eq(&self, other: &Self) -> bool {
+        self.slot_count_exp == other.slot_count_exp
+            && self.slot_size_exp == other.slot_size_exp
+            && self.max_time() == other.max_time()
+            && self
+                .slots
+                .iter()
+                .zip(other.slots.iter())
+                .all(|(mine, theirs)| {
+                    mine.load(&self.orderings().count_in)
+                        == theirs.load(&other.orderings().count_in)
+                })
+    }
+}
+
+impl Eq for InvocationCounter {}
+
+/// Prints a one-line summary: the geometry, the latest observed time, the number of
+/// invocations in the window and the `interval_start:count` pairs of its non-empty intervals.
+///
+/// # Examples
+///
+/// ```rust
+/// # use invocation_counter::InvocationCounter;
+/// let counter = InvocationCounter::new(3, 4);
+///
+/// counter.register(10);
+/// counter.register(12);
+/// counter.register(40);
+///
+/// assert_eq!(
+///     counter.to_string(),
+///     "8 slots × 16 = 128 units, latest 40, total 3: [0:2, 32:1]"
+/// );
+/// ```
+impl fmt::Display for InvocationCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Read the intervals first, so the total and latest time are at least as recent
+        let intervals = self.snapshot();
+        write!(
+            f,
+            "{} slots × {} = {} units, latest {}, total {}: [",
+            self.num_slots(),
+            self.slot_size(),
+            self.saturating_window_size(),
+            self.max_time(),
+            intervals
+                .iter()
+                .map(|&(_, count)| count as u64)
+                .sum::<u64>()
+        )?;
+        for (index, (interval_start, count)) in intervals.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{interval_start}:{count}")?;
+        }
+        f.write_str("]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_basic_functionality() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register(0);
+        counter.register(1);
+        counter.register(8);
+        counter.register(16);
+
+        // All within 32-unit window from time 16
+        assert_eq!(counter.count_in(0, 16 + 1), 4);
+
+        // All outside window from time 100
+        assert_eq!(counter.count_in(100 - 32, 100 + 1), 0);
+    }
+
+    #[test]
+    fn test_count() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(counter.count(), 0);
+
+        for time in [0, 5, 9, 20, 31] {
+            counter.register(time);
+        }
+        assert_eq!(counter.count(), 5);
+
+        // The window is now [8, 40)
+        counter.register(33);
+        assert_eq!(counter.count(), 4);
+        assert_eq!(counter.count(), counter.count_in(8, 40));
+
+        // Moving time forward without registering empties the window
+        counter.advance_time(100);
+        assert_eq!(counter.count(), 0);
+        counter.register(100);
+        assert_eq!(counter.count(), 1);
+    }
+
+    #[test]
+    fn test_register_n() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register_n(3, 10);
+        counter.register_n(5, 5);
+        counter.register_n(12, 7);
+        assert_eq!(counter.count_in(0, 8), 15);
+        assert_eq!(counter.count_in(8, 16), 7);
+        assert_eq!(counter.lifetime_count(), 22);
+
+        // Resets a slot to `count`
+        counter.register_n(33, 4);
+        assert_eq!(counter.count_in(32, 40), 4);
+        assert_eq!(counter.count_in(0, 8), 0);
+
+        // Zero only advances time, like a registration would
+        let zero = InvocationCounter::new(2, 3);
+        zero.register_n(0, 3);
+        zero.register_n(35, 0);
+        assert_eq!(zero.count(), 0);
+        assert_eq!(zero.count_in(0, 8), 0); // Evicted from the window by the time advance
+        assert_eq!(zero.lifetime_count(), 3);
+        // The slot of [0, 8) still holds its old interval
+        assert_eq!(zero.slots[0].interval_start.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_geometry_getters() {
+        let counter = InvocationCounter::new(4, 2);
+        assert_eq!(counter.num_slots(), 16);
+        assert_eq!(counter.slot_size(), 4);
+        assert_eq!(counter.window_size(), 64);
+
+        let counter = InvocationCounter::new(0, 0);
+        assert_eq!(counter.num_slots(), 1);
+        assert_eq!(counter.slot_size(), 1);
+        assert_eq!(counter.window_size(), 1);
+
+        let counter = InvocationCounter::new(2, 63);
+        assert_eq!(counter.slot_size(), 1 << 63);
+        assert_eq!(counter.window_size(), u64::MAX);
+    }
+
+    #[test]
+    fn test_empty_slots_are_never_counted() {
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(counter.count_in(0, 100), 0);
+
+        let mut visited = 0;
+        counter.scan_full(0..u64::MAX, |_, _| visited += 1);
+        assert_eq!(visited, 0);
+
+        // Only the slot of [0, 8) tracks an interval after registering at time 0
+        counter.register(0);
+        let mut visited = Vec::new();
+        counter.scan_full(0..u64::MAX, |interval_start, count| {
+            visited.push((interval_start, count))
+        });
+        assert_eq!(visited, vec![(0, 1)]);
+
+        // Clearing empties the slots again
+        counter.clear();
+        let mut visited = 0;
+        counter.scan_full(0..u64::MAX, |_, _| visited += 1);
+        assert_eq!(visited, 0);
+    }
+
+    #[test]
+    fn test_no_phantom_counts_when_ring_start_saturates() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        let fixed = StaticInvocationCounter::<4>::new(3);
+        for time in [20, 25, 30] {
+            counter.register(time);
+            fixed.register(time);
+        }
+
+        // The window [0, 32) starts at 0, where the never-written slots of [0, 8) and [8, 16)
+        // would be if empty slots tracked interval 0
+        assert_eq!(counter.ring_range(), 0..32);
+        assert_eq!(counter.count_in(0, 8), 0);
+        assert_eq!(counter.count_in(0, 16), 0);
+        assert_eq!(counter.count_in(0, 32), 3);
+        assert_eq!(counter.count_in_many(&[(0, 8), (0, 16)]), vec![0, 0]);
+        assert_eq!(counter.snapshot(), vec![(16, 1), (24, 2)]);
+        assert_eq!(fixed.count_in(0, 16), 0);
+        assert_eq!(fixed.count_in(0, 32), 3);
+
+        let counter = InvocationCounter::new(2, 3);
+        counter.register(u64::MAX - 1);
+        assert_eq!(counter.count_in(0, u64::MAX), 1);
+        assert_eq!(counter.count_in(0, 1 << 40), 0);
+    }
+
+    #[test]
+    fn test_window_bounds() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(counter.window_bounds(), (0, 8));
+
+        counter.register(20);
+        assert_eq!(counter.window_bounds(), (0, 24));
+
+        counter.register(100);
+        let (start, end) = counter.window_bounds();
+        assert_eq!((start, end), (72, 104));
+        // Queries are clamped to the bounds
+        assert_eq!(counter.count_in(0, u64::MAX), counter.count_in(start, end));
+
+        counter.register(u64::MAX);
+        assert_eq!(counter.window_bounds(), (u64::MAX - 32, u64::MAX));
+    }
+
+    #[test]
+    fn test_large_slot_count() {
+        // 2^20 slots * 1 time unit (2^0) = 2^20 time units window
+        let counter = InvocationCounter::new(20, 0);
+        assert_eq!(counter.num_slots(), 1 << 20);
+        assert_eq!(counter.slot_count_mask, (1 << 20) - 1);
+
+        // The first, last and wrapped-around slots
+        let last = (1u64 << 20) - 1;
+        for time in [0, 1, last - 1, last] {
+            counter.register(time);
+        }
+        assert_eq!(counter.count_in(0, 1 << 20), 4);
+        assert_eq!(counter.count_in(last, last + 1), 1);
+
+        counter.register(last + 1); // Reuses the slot of interval 0
+        assert_eq!(counter.count_in(0, 1), 0);
+        assert_eq!(counter.count_in(last + 1, last + 2), 1);
+        assert_eq!(counter.count_in(0, u64::MAX), 4);
+        assert_eq!(counter.snapshot()[0], (1, 1));
+    }
+
+    #[test]
+    fn test_register_and_count() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        assert_eq!(counter.register_and_count(10, 8), 1);
+        assert_eq!(counter.register_and_count(11, 8), 2);
+        // [8, 16) and [16, 20)
+        assert_eq!(counter.register_and_count(17, 8), 3);
+        // [16, 24): the window is aligned to slot boundaries
+        assert_eq!(counter.register_and_count(20, 8), 2);
+        // Clamped to the counter's window
+        assert_eq!(counter.register_and_count(21, u64::MAX), 5);
+        assert_eq!(counter.register_and_count(22, 0), 0);
+        assert_eq!(counter.lifetime_count(), 6);
+
+        // Out of order: the range still ends at `now`
+        assert_eq!(counter.register_and_count(12, 4), 3);
+        assert_eq!(counter.count_last(4), 4); // [16, 24), ending at the latest time
+
+        // A rejected registration isn't counted
+        counter.pause();
+        assert_eq!(counter.register_and_count(23, 4), 3);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let max = InvocationCounter::MAX_SLOT_COUNT_EXP;
+        assert!(InvocationCounter::try_new(0, 0).is_ok());
+        assert!(InvocationCounter::try_new(4, 63).is_ok()); // The window saturates
+        assert_eq!(
+            InvocationCounter::try_new(max + 1, 0).unwrap_err(),
+            CounterError::InvalidConfig {
+                reason: "slot_count_exp must be at most MAX_SLOT_COUNT_EXP"
+            }
+        );
+        assert_eq!(
+            InvocationCounter::try_new(2, 64).unwrap_err(),
+            CounterError::InvalidConfig {
+                reason: "slot_size_exp must be below 64"
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "InvocationCounter::new(40, 40): invalid counter configuration")]
+    fn test_new_panics_on_invalid_config() {
+        InvocationCounter::new(40, 40);
+    }
+
+    #[test]
+    fn test_count_last() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        assert_eq!(counter.count_last(10), 0);
+
+        for time in [2, 9, 17, 18, 25, 30] {
+            counter.register(time);
+        }
+
+        // The latest time is 30, in the interval [28, 32)
+        assert_eq!(counter.count_last(0), 0);
+        assert_eq!(counter.count_last(1), 1);
+        assert_eq!(counter.count_last(3), 1); // [28, 31)
+        assert_eq!(counter.count_last(4), 2); // [27, 31) reaches into [24, 28)
+        assert_eq!(counter.count_last(14), 4); // [17, 31)
+        assert_eq!(counter.count_last(32), 6);
+        // Clamped to the window
+        assert_eq!(counter.count_last(u64::MAX), 6);
+        assert_eq!(counter.count_last(u64::MAX), counter.count());
+
+        counter.register(40);
+        assert_eq!(counter.count_last(u64::MAX), 5);
+    }
+
+    #[test]
+    fn test_count_in() {
+        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
+        // Slot 0: times 0-3 (interval_start = 0)
+        // Slot 1: times 4-7 (interval_start = 4)
+        let counter = InvocationCounter::new(1, 2);
+
+        counter.register(0);
+
+        // All registrations 0,1,2,3 go to the same slot (interval_start = 0)
+        // count_in works on slot boundaries, so any range that includes slot 0 gets all counts
+        assert_eq!(counter.count_in(0, 1), 1); // Includes slot 0
+        assert_eq!(counter.count_in(0, 4), 1); // Includes slot 0
+        assert_eq!(counter.count_in(0, 8), 1); // Includes slot 0
+        assert_eq!(counter.count_in(4, 8), 0); // Only includes slot 1 (empty)
+
+        counter.register(1);
+        // Still all in slot 0
+        assert_eq!(counter.count_in(0, 4), 2); // Includes slot 0
+        assert_eq!(counter.count_in(0, 8), 2); // Includes slot 0
+        assert_eq!(counter.count_in(4, 8), 0); // Only slot 1
+
+        counter.register(2);
+        assert_eq!(counter.count_in(0, 4), 3); // Slot 0
+        assert_eq!(counter.count_in(4, 8), 0); // Slot 1
+
+        counter.register(3);
+        assert_eq!(counter.count_in(0, 4), 4); // Slot 0
+        assert_eq!(counter.count_in(4, 8), 0); // Slot 1
+
+        // Register 4 in slot 1 (interval_start = 4)
+        counter.register(4);
+        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only (end_time=4 excludes slot 1)
+        assert_eq!(counter.count_in(4, 8), 1); // Slot 1 only
+        assert_eq!(counter.count_in(0, 8), 5); // Both slots
+
+        counter.register(5);
+        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
+        assert_eq!(counter.count_in(4, 8), 2); // Slot 1 only
+        assert_eq!(counter.count_in(0, 8), 6); // Both slots
+
+        counter.register(6);
+        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
+        assert_eq!(counter.count_in(4, 8), 3); // Slot 1 only
+        assert_eq!(counter.count_in(0, 8), 7); // Both slots
+
+        counter.register(7);
+        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
+        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 only
+        assert_eq!(counter.count_in(0, 8), 8); // Both slots
+        assert_eq!(counter.count_in(4, 12), 4); // Slot 1 only
+        assert_eq!(counter.count_in(12, 16), 0); // Out of range
+
+        // Register 8 - wraps to slot 0, resets it (interval_start = 8)
+        counter.register(8);
+        assert_eq!(counter.count_in(0, 4), 0); // Old slot 0 data gone
+        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
+        assert_eq!(counter.count_in(8, 12), 1); // New slot 0
+        assert_eq!(counter.count_in(4, 12), 5); // Slot 1 + new slot 0
+        assert_eq!(counter.count_in(8, 9), 1); // Within new slot 0
+        assert_eq!(counter.count_in(0, 12), 5); // Full range
+
+        counter.register(9);
+        assert_eq!(counter.count_in(0, 4), 0); // Old slot 0 still gone
+        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
+        assert_eq!(counter.count_in(8, 12), 2); // New slot 0 has 2 entries
+        assert_eq!(counter.count_in(4, 12), 6); // Slot 1 + new slot 0
+        assert_eq!(counter.count_in(8, 10), 2); // Within new slot 0
+        assert_eq!(counter.count_in(0, 16), 6); // Full range
+
+        counter.register(10);
+        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
+        assert_eq!(counter.count_in(8, 12), 3); // New slot 0 has 3 entries
+        assert_eq!(counter.count_in(4, 12), 7); // Slot 1 + new slot 0
+        assert_eq!(counter.count_in(8, 11), 3); // Within new slot 0
+        assert_eq!(counter.count_in(0, 16), 7); // Full range
+
+        counter.register(11);
+        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
+        assert_eq!(counter.count_in(8, 12), 4); // New slot 0 has 4 entries
+        assert_eq!(counter.count_in(4, 12), 8); // Slot 1 + new slot 0
+        assert_eq!(counter.count_in(8, 12), 4); // Full new slot 0
+        assert_eq!(counter.count_in(10, 12), 4); // Includes full slot 0 (slot-boundary aligned)
+        assert_eq!(counter.count_in(0, 16), 8); // Full range
+
+        // Register 12 - wraps to slot 1, resets it (interval_start = 12)
+        counter.register(12);
+        assert_eq!(counter.count_in(4, 8), 0); // Old slot 1 data gone
+        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
+        assert_eq!(counter.count_in(12, 16), 1); // New slot 1
+        assert_eq!(counter.count_in(8, 16), 5); // Slot 0 + new slot 1
+        assert_eq!(counter.count_in(0, 16), 5); // Full range
+        assert_eq!(counter.count_in(12, 13), 1); // Within new slot 1
+
+        counter.register(13);
+        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
+        assert_eq!(counter.count_in(12, 16), 2); // New slot 1 has 2 entries
+        assert_eq!(counter.count_in(8, 16), 6); // Slot 0 + new slot 1
+        assert_eq!(counter.count_in(0, 16), 6); // Full range
+        assert_eq!(counter.count_in(12, 14), 2); // Within new slot 1
+
+        counter.register(14);
+        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
+        assert_eq!(counter.count_in(12, 16), 3); // New slot 1 has 3 entries
+        assert_eq!(counter.count_in(8, 16), 7); // Slot 0 + new slot 1
+        assert_eq!(counter.count_in(0, 16), 7); // Full range
+        assert_eq!(counter.count_in(12, 15), 3); // Within new slot 1
+
+        counter.register(15);
+        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
+        assert_eq!(counter.count_in(12, 16), 4); // New slot 1 has 4 entries
+        assert_eq!(counter.count_in(8, 16), 8); // Slot 0 + new slot 1
+        assert_eq!(counter.count_in(0, 16), 8); // Full range
+        assert_eq!(counter.count_in(12, 16), 4); // Full new slot 1
+
+        // Register 16 - wraps to slot 0 again, resets it (interval_start = 16)
+        counter.register(16);
+        assert_eq!(counter.count_in(8, 12), 0); // Old slot 0 data gone
+        assert_eq!(counter.count_in(12, 16), 4); // Slot 1 unchanged
+        assert_eq!(counter.count_in(16, 20), 1); // New slot 0
+        assert_eq!(counter.count_in(12, 20), 5); // Slot 1 + new slot 0
+        assert_eq!(counter.count_in(0, 20), 5); // Full range
+        assert_eq!(counter.count_in(16, 17), 1); // Within new slot 0
+    }
+
+    #[test]
+    fn test_slot_reuse() {
+        // 4 slots (2^2) * 4 time units (2^2) = 16 time units window
+        let counter = InvocationCounter::new(2, 2);
+
+        counter.register(0); // slot 0
+        counter.register(16); // wraps to slot 0, resets counter
+
+        assert_eq!(counter.count_in(0, 17), 1); // Only recent registration
+    }
+
+    #[test]
+    fn test_concurrent_access() {
+        let num_threads = 4;
+        let registrations_per_thread = 100;
+
+        // 8 slots (2^3) * 64 time units (2^6) = 512 time units window
+        let counter = Arc::new(InvocationCounter::new(3, 6));
+
+        let handles: Vec<_> = (0..num_threads)
+            .map(|thread_id| {
+                let counter_clone = Arc::clone(&counter);
+                thread::spawn(move || {
+                    for i in 0..registrations_per_thread {
+                        counter_clone.register(thread_id as u64 * 10 + i as u64);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let count = counter.count_in(0, 399); // Query at end of all registrations
+        assert!(count > 0);
+        assert!(count <= num_threads * registrations_per_thread);
+    }
+
+    #[test]
+    fn test_concurrent_max_time() {
+        let num_threads = 8;
+        let registrations_per_thread = 10_000;
+
+        // 16 slots (2^4) * 1 time unit (2^0) = 16 time units window
+        let counter = Arc::new(InvocationCounter::new(4, 0));
+
+        let handles: Vec<_> = (0..num_threads)
+            .map(|thread_id| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    for i in 0..registrations_per_thread {
+                        counter.register(i * num_threads + thread_id);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let true_max = registrations_per_thread * num_threads - 1;
+        assert_eq!(counter.max_current_time.load(Ordering::Relaxed), true_max);
+        // The window ends right after the true maximum
+        assert!(counter.is_in_window(true_max));
+        assert!(!counter.is_in_window(true_max - 16));
+    }
+
+    #[test]
+    fn test_concurrent_reads_see_consistent_slots() {
+        let laps: u64 = 2_000;
+        let registrations_per_interval = 3;
+        // Intervals of even laps are registered with a weight of 1000 and those of odd laps
+        // with a weight of 1, so a count paired with the interval it replaced (or that replaced
+        // it) breaks the bounds below
+        let weight = |interval: u64| -> u32 {
+            if (interval / 4).is_multiple_of(2) {
+                1000
+            } else {
+                1
+            }
+        };
+
+        // 4 slots (2^2) * 1 time unit (2^0) = 4 time units window
+        let counter = InvocationCounter::new(2, 0);
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            let readers: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        while !done.load(Ordering::Acquire) {
+                            for (interval_start, count) in counter.snapshot() {
+                                let max = registrations_per_interval * weight(interval_start);
+                                assert!(count <= max, "{count} in interval {interval_start}");
+                                assert!(count.is_multiple_of(weight(interval_start)));
+                            }
+                            assert!(counter.count() <= 4 * registrations_per_interval * 1000);
+                        }
+                    })
+                })
+                .collect();
+
+            // Each writer owns two of the slots, so rollovers only race with readers
+            let writers: Vec<_> = (0..2)
+                .map(|writer| {
+                    let counter = &counter;
+                    scope.spawn(move || {
+                        for interval in (writer..laps * 4).step_by(2) {
+                            for _ in 0..registrations_per_interval {
+                                counter.register_n(interval, weight(interval));
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            for reader in readers {
+                reader.join().unwrap();
+            }
+        });
+
+        assert!(counter.count() > 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let shard_a = InvocationCounter::new(2, 3);
+        let shard_b = InvocationCounter::new(2, 3);
+        let combined = InvocationCounter::new(2, 3);
+
+        for time in [1, 2, 9, 20, 25] {
+            shard_a.register(time);
+            combined.register(time);
+        }
+        for time in [3, 10, 11, 30] {
+            shard_b.register(time);
+            combined.register(time);
+        }
+
+        shard_a.merge(&shard_b).unwrap();
+        for start in 0..40 {
+            for end in start..40 {
+                assert_eq!(shard_a.count_in(start, end), combined.count_in(start, end));
+            }
+        }
+        assert_eq!(shard_a.lifetime_count(), 9);
+        assert_eq!(shard_b.count(), 4);
+
+        // Newer intervals win over older ones in both directions
+        let old = InvocationCounter::new(2, 3);
+        let new = InvocationCounter::new(2, 3);
+        old.register(1);
+        old.register(40);
+        new.register(33);
+        new.register(8);
+        old.merge(&new).unwrap();
+        // [0, 8) is replaced by [32, 40), [8, 16) is adopted but out of the window
+        assert_eq!(old.count_in(32, 48), 2);
+        assert_eq!(old.count_in(0, 16), 0);
+        assert_eq!(old.count(), 2);
+
+        let mismatched = InvocationCounter::new(2, 4);
+        assert_eq!(
+            shard_a.merge(&mismatched),
+            Err(CounterError::GeometryMismatch {
+                expected: (2, 3),
+                found: (2, 4),
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_window() {
+        let geometry = |total: Duration, resolution: Duration| {
+            InvocationCounter::from_window(total, resolution)
+                .map(|counter| (counter.slot_count_exp(), counter.slot_size_exp()))
+        };
+        let ms = Duration::from_millis;
+
+        // Exact powers of two are kept
+        assert_eq!(geometry(ms(1024), ms(16)), Ok((6, 4)));
+        // Both sides round up
+        assert_eq!(geometry(ms(1025), ms(17)), Ok((6, 5)));
+        assert_eq!(
+            geometry(Duration::from_secs(3600), Duration::from_secs(1)),
+            Ok((12, 10))
+        );
+        // Sub-millisecond durations round up to a millisecond
+        assert_eq!(
+            geometry(Duration::from_micros(1500), Duration::ZERO),
+            Ok((1, 0))
+        );
+        assert_eq!(
+            geometry(Duration::ZERO, Duration::from_nanos(1)),
+            Ok((0, 0))
+        );
+        // A window shorter than the resolution is a single slot
+        assert_eq!(geometry(ms(10), ms(100)), Ok((0, 7)));
+
+        assert!(geometry(Duration::from_secs(1 << 25), ms(1)).is_err());
+        assert!(geometry(Duration::MAX, Duration::MAX).is_err());
+    }
+
+    #[test]
+    fn test_single_unit_slots() {
+        // 8 slots (2^3) * 1 time unit (2^0) = 8 time units window
+        let counter = InvocationCounter::new(3, 0);
+        for time in 100..108 {
+            counter.register_n(time, (time - 99) as u32);
+        }
+
+        for time in 100..108 {
+            assert_eq!(counter.count_in(time, time + 1), (time - 99) as u32);
+            assert_eq!(counter.count_range(time..=time), (time - 99) as u32);
+        }
+        assert_eq!(counter.count_in(100, 100), 0);
+        assert_eq!(counter.count_in(103, 105), 4 + 5);
+        assert_eq!(counter.count_in(0, 108), 36);
+        assert_eq!(counter.count_last(1), 8);
+        assert_eq!(counter.count_last(3), 6 + 7 + 8);
+
+        // Every new unit evicts exactly the oldest one
+        counter.register(108);
+        assert_eq!(counter.count_in(100, 101), 0);
+        assert_eq!(counter.count_in(101, 109), 36 - 1 + 1);
+    }
+
+    #[test]
+    fn test_single_slot() {
+        // 1 slot (2^0) * 8 time units (2^3) = 8 time units window
+        let counter = InvocationCounter::new(0, 3);
+        assert_eq!(counter.num_slots(), 1);
+        assert_eq!(counter.window_size(), 8);
+
+        counter.register(1);
+        counter.register(7);
+        assert_eq!(counter.count_in(0, 8), 2);
+        assert_eq!(counter.count_in(0, 1), 2);
+        assert_eq!(counter.count(), 2);
+
+        // A new interval resets the slot
+        counter.register(9);
+        assert_eq!(counter.count_in(0, 8), 0);
+        assert_eq!(counter.count_in(8, 16), 1);
+        assert_eq!(counter.count_in(0, 100), 1);
+        assert_eq!(counter.snapshot(), vec![(8, 1)]);
+
+        // Older intervals are dropped rather than clobbering the current one
+        assert!(!counter.try_register(3));
+        counter.register(40);
+        assert_eq!(counter.count_in(8, 16), 0);
+        assert_eq!(counter.count_in(40, 41), 1);
+        assert_eq!(counter.count_last(1), 1);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        // Empty counter
+        assert_eq!(counter.count_in(0, 0), 0);
+
+        // Zero time
+        counter.register(0);
+        assert_eq!(counter.count_in(0, 1), 1);
+
+        // Large time values
+        let large_time = 1_000_000u64;
+        counter.register(large_time);
+        assert_eq!(counter.count_in(large_time, large_time + 1), 1);
+    }
+
+    #[test]
+    fn test_swap_contents() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let a = InvocationCounter::new(2, 3);
+        let b = InvocationCounter::new(2, 3);
+
+        a.register(1);
+        a.register(2);
+        a.register(9);
+
+        b.register(40);
+        b.register(50);
+
+        a.swap_contents(&b).unwrap();
+
+        // `a` now holds what `b` had
+        assert_eq!(a.count_in(0, 16), 0);
+        assert_eq!(a.count_in(40, 48), 1);
+        assert_eq!(a.count_in(48, 56), 1);
+
+        // `b` now holds what `a` had
+        assert_eq!(b.count_in(0, 8), 2);
+        assert_eq!(b.count_in(8, 16), 1);
+        assert_eq!(b.count_in(40, 56), 0);
+
+        // The latest observed time and totals move along with the slots
+        assert_eq!(a.max_time(), 50);
+        assert_eq!(b.max_time(), 9);
+        assert_eq!(a.lifetime_count(), 2);
+        assert_eq!(b.lifetime_count(), 3);
+
+        // Swapping back restores the original state
+        a.swap_contents(&b).unwrap();
+        assert_eq!(a.count_in(0, 16), 3);
+        assert_eq!(b.count_in(40, 56), 2);
+        assert_eq!(a.max_time(), 9);
+        assert_eq!(b.max_time(), 50);
+
+        // Swapping with itself is a no-op
+        a.swap_contents(&a).unwrap();
+        assert_eq!(a.count_in(0, 16), 3);
+    }
+
+    #[test]
+    fn test_swap_contents_concurrent_register() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let a = InvocationCounter::new(2, 3);
+        let b = InvocationCounter::new(2, 3);
+        let registrations = 10_000;
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..registrations {
+                    b.register(1);
+                }
+            });
+            for _ in 0..1_000 {
+                a.swap_contents(&b).unwrap();
+            }
+        });
+
+        // No registration landing in `b` mid-swap is lost
+        assert_eq!(a.count_in(0, 8) + b.count_in(0, 8), registrations);
+        assert_eq!(
+            a.lifetime_count() + b.lifetime_count(),
+            registrations as u64
+        );
+    }
+
+    #[test]
+    fn test_swap_contents_geometry_mismatch() {
+        let a = InvocationCounter::new(2, 3);
+        let b = InvocationCounter::new(3, 3);
+
+        a.register(1);
+
+        let err = a.swap_contents(&b).unwrap_err();
+        assert_eq!(
+            err,
+            CounterError::GeometryMismatch {
+                expected: (2, 3),
+                found: (3, 3),
+            }
+        );
+
+        // Nothing was exchanged
+        assert_eq!(a.count_in(0, 8), 1);
+        assert_eq!(b.count_in(0, 8), 0);
+    }
+
+    #[test]
+    fn test_lifetime_count() {
+        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
+        let counter = InvocationCounter::new(1, 2);
+
+        for t in 0..100 {
+            counter.register(t);
+        }
+
+        // The window only retains the last two intervals
+        assert_eq!(counter.count_in(0, 100), 8);
+        // The lifetime count is exact
+        assert_eq!(counter.lifetime_count(), 100);
+
+        counter.reset_lifetime();
+        assert_eq!(counter.lifetime_count(), 0);
+        assert_eq!(counter.count_in(0, 100), 8);
+
+        counter.register(100);
+        assert_eq!(counter.lifetime_count(), 1);
+    }
+
+    #[test]
+    fn test_count_in_saturating() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register(1);
+        counter.register(9);
+        assert_eq!(counter.count_in_saturating(0, 16), (2, false));
+
+        // Drive the slot for interval [8, 16) to saturation
+        counter.slots[1].counter.store(u32::MAX, Ordering::Release);
+
+        assert_eq!(
+            counter.count_in_saturating(0, 16),
+            (u32::MAX as u64 + 1, true)
+        );
+        assert_eq!(counter.count_in_saturating(8, 16), (u32::MAX as u64, true));
+        // The saturated slot is outside the queried range
+        assert_eq!(counter.count_in_saturating(0, 8), (1, false));
+        // Empty range
+        assert_eq!(counter.count_in_saturating(8, 8), (0, false));
+    }
+
+    #[test]
+    fn test_geometry_comparison() {
+        // 16 slots * 4 time units = 64 time units window
+        let fine = InvocationCounter::new(4, 2);
+        // 4 slots * 32 time units = 128 time units window
+        let coarse = InvocationCounter::new(2, 5);
+        // 2 slots * 2^63 time units: larger than any u64 window
+        let huge = InvocationCounter::new(1, 63);
+
+        assert!(fine.covers_at_least(0));
+        assert!(fine.covers_at_least(64));
+        assert!(!fine.covers_at_least(65));
+        assert!(coarse.covers_at_least(65));
+        assert!(coarse.covers_at_least(128));
+        assert!(!coarse.covers_at_least(129));
+        assert!(huge.covers_at_least(u64::MAX));
+
+        assert!(fine.finer_than(&coarse));
+        assert!(!coarse.finer_than(&fine));
+        assert!(!fine.finer_than(&InvocationCounter::new(1, 2)));
+        assert!(coarse.finer_than(&huge));
+    }
+
+    #[test]
+    fn test_register_n_observed() {
+        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
+        let counter = InvocationCounter::new(1, 2);
+
+        // Fresh slot: nothing to evict
+        assert_eq!(
+            counter.register_n_observed(0, 3),
+            RegisterOutcome {
+                added: 3,
+                evicted: 0
+            }
+        );
+        // Same interval: added to the existing count
+        assert_eq!(
+            counter.register_n_observed(2, 4),
+            RegisterOutcome {
+                added: 4,
+                evicted: 0
+            }
+        );
+        assert_eq!(counter.count_in(0, 4), 7);
+
+        // Interval [8, 12) reuses slot 0 and evicts the 7 invocations of [0, 4)
+        assert_eq!(
+            counter.register_n_observed(9, 10),
+            RegisterOutcome {
+                added: 10,
+                evicted: 7
+            }
+        );
+        assert_eq!(counter.count_in(0, 4), 0);
+        assert_eq!(counter.count_in(8, 12), 10);
+        assert_eq!(counter.lifetime_count(), 17);
+    }
+
+    #[test]
+    fn test_register_n_observed_concurrent_eviction_reported_once() {
+        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
+        let counter = Arc::new(InvocationCounter::new(1, 2));
+        counter.register_n_observed(0, 100);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || counter.register_n_observed(8, 1).evicted)
+            })
+            .collect();
+
+        let evictions: Vec<u32> = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|evicted| *evicted > 0)
+            .collect();
+        // Exactly one thread rolled the slot over
+        assert_eq!(evictions.len(), 1);
+        assert!(evictions[0] >= 100);
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[test]
+    fn test_out_of_order_count() {
+        let counter = InvocationCounter::new(3, 4);
+
+        counter.register(10);
+        counter.register(20);
+        counter.register(20); // Same time is not out of order
+        assert_eq!(counter.out_of_order_count(), 0);
+
+        counter.register(15);
+        counter.register(5);
+        assert_eq!(counter.out_of_order_count(), 2);
+
+        counter.register(30);
+        assert_eq!(counter.out_of_order_count(), 2);
+
+        // Every registration is still recorded
+        assert_eq!(counter.count_in(0, 32), 6);
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[test]
+    fn test_reset_increment_ratio() {
+        let coarse = InvocationCounter::new(3, 10);
+        let fine = InvocationCounter::new(3, 0);
+        assert_eq!(coarse.reset_increment_ratio(), 0.0);
+
+        // One registration every 4 time units
+        for time in (4..4_000).step_by(4) {
+            coarse.register(time);
+            fine.register(time);
+        }
+
+        // 1024 time units per slot: a handful of resets, mostly increments
+        assert!(coarse.reset_increment_ratio() < 0.01);
+        // 1 time unit per slot: every registration lands in a fresh interval
+        assert_eq!(fine.reset_increment_ratio(), f64::INFINITY);
+
+        let medium = InvocationCounter::new(3, 1);
+        for time in 0..100 {
+            medium.register(time);
+        }
+        // Intervals of 2: the first of each pair resets
+        assert_eq!(medium.reset_increment_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_active_intervals_in() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let concentrated = InvocationCounter::new(4, 2);
+        let spread = InvocationCounter::new(4, 2);
+
+        for i in 0..16 {
+            concentrated.register(20 + i % 4);
+            spread.register(i * 4);
+        }
+
+        assert_eq!(concentrated.count_in(0, 64), 16);
+        assert_eq!(spread.count_in(0, 64), 16);
+
+        assert_eq!(concentrated.active_intervals_in(0, 64), 1);
+        assert_eq!(spread.active_intervals_in(0, 64), 16);
+
+        // Sub-ranges are aligned to slot boundaries
+        assert_eq!(spread.active_intervals_in(8, 20), 3);
+        assert_eq!(concentrated.active_intervals_in(0, 20), 0);
+        assert_eq!(concentrated.active_intervals_in(10, 10), 0);
+    }
+
+    #[test]
+    fn test_register_if() {
+        let counter = InvocationCounter::new(3, 4);
+
+        for t in 0..10 {
+            assert!(!counter.register_if(t, || false));
+        }
+        assert_eq!(counter.count_in(0, 16), 0);
+        assert_eq!(counter.lifetime_count(), 0);
+
+        for t in 0..10 {
+            assert!(counter.register_if(t, || true));
+        }
+        assert_eq!(counter.count_in(0, 16), 10);
+        assert_eq!(counter.lifetime_count(), 10);
+    }
+
+    #[test]
+    fn test_window_exceeding_u64() {
+        // 2 slots (2^1) * 2^63 time units: the window doesn't fit in a u64
+        let counter = InvocationCounter::new(1, 63);
+
+        counter.register(5);
+        assert_eq!(counter.count_in(0, 10), 1);
+
+        counter.register(1_000_000);
+        assert_eq!(counter.count_in(0, 1_000_001), 2);
+    }
+
+    #[test]
+    fn test_spillover() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register(1);
+        counter.register_n_observed(9, u32::MAX - 2);
+        assert_eq!(counter.spillover(), 0);
+
+        // Push the slot of [8, 16) past u32::MAX
+        counter.register(10);
+        counter.register(11);
+        counter.register(12);
+        counter.register_n_observed(13, 10);
+
+        let (windowed, saturated) = counter.count_in_saturating(0, 32);
+        assert!(saturated);
+        assert_eq!(counter.count_in(8, 16), u32::MAX);
+        assert_eq!(counter.spillover(), 11);
+
+        // The window total is still exact once the spillover is added back
+        let exact = 1 + (u32::MAX as u64 - 2) + 3 + 10;
+        assert_eq!(windowed + counter.spillover(), exact);
+        assert_eq!(counter.lifetime_count(), exact);
+    }
+
+    #[test]
+    fn test_register_n_saturates() {
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register_n(10, u32::MAX - 1);
+        counter.register_n(11, 5);
+        counter.register(12);
+        assert_eq!(counter.count_in(8, 16), u32::MAX);
+        assert_eq!(counter.spillover(), 5);
+
+        // A rollover starts the slot over from the registered count
+        counter.register_n(40, 3);
+        assert_eq!(counter.count_in(40, 48), 3);
+    }
+
+    #[test]
+    fn test_try_acquire() {
+        // 16 slots (2^4) * 2 time units (2^1) = 32 time units window
+        let counter = InvocationCounter::new(4, 1);
+
+        // At most 3 calls per 8 time units
+        let allowed: Vec<_> = (100..110)
+            .map(|now| counter.try_acquire(now, 3, 8))
+            .collect();
+        // At 108, [101, 109) is aligned to [100, 110) and still sees the call at 100
+        assert_eq!(
+            allowed,
+            [true, true, true, false, false, false, false, false, false, true]
+        );
+        // Denied calls aren't registered
+        assert_eq!(counter.count_in(100, 110), 4);
+
+        assert!(!counter.try_acquire(109, 0, 8));
+        // A window beyond the counter's is clamped to it
+        assert!(!counter.try_acquire(120, 4, 1_000));
+        assert!(counter.try_acquire(120, 5, 1_000));
+    }
+
+    #[test]
+    fn test_register_weighted() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        counter.register_weighted(1, 40);
+        counter.register_weighted(3, 2);
+        counter.register_weighted(9, 7);
+        assert_eq!(counter.count_in(0, 8), 42);
+        assert_eq!(counter.count(), 49);
+
+        // Saturates like register_n, with the excess in the spillover
+        counter.register_weighted(10, u32::MAX);
+        assert_eq!(counter.count_in(8, 16), u32::MAX);
+        assert_eq!(counter.spillover(), 7);
+        assert_eq!(counter.lifetime_count(), 49 + u32::MAX as u64);
+    }
+
+    #[test]
+    fn test_checked_register() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        counter.register_n(9, u32::MAX - 2);
+        assert_eq!(counter.checked_register(10), Ok(()));
+        assert_eq!(counter.checked_register(11), Ok(()));
+        assert_eq!(counter.count_in(8, 16), u32::MAX);
+
+        // The slot is saturated: every further invocation goes to the spillover
+        for time in [12, 15] {
+            assert_eq!(
+                counter.checked_register(time),
+                Err(CounterError::Overflow { interval_start: 8 })
+            );
+        }
+        assert_eq!(counter.spillover(), 2);
+        assert_eq!(counter.count_in(8, 16), u32::MAX);
+
+        // Other slots are unaffected
+        assert_eq!(counter.checked_register(20), Ok(()));
+        // A new interval starts over
+        counter.register_n(41, u32::MAX);
+        assert_eq!(
+            counter.checked_register(41),
+            Err(CounterError::Overflow { interval_start: 40 })
+        );
+        counter.register(50);
+        assert_eq!(counter.checked_register(72), Ok(()));
+    }
+
+    #[test]
+    fn test_count_in_u64() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for interval in 0..8 {
+            counter.register_n(interval * 4, u32::MAX);
+        }
+
+        let exact = 8 * u32::MAX as u64;
+        assert_eq!(counter.count_in_u64(0, 32), exact);
+        assert_eq!(counter.count_in_saturating(0, 32), (exact, true));
+        // The u32 path saturates instead of wrapping
+        assert_eq!(counter.count_in(0, 32), u32::MAX);
+        assert_eq!(counter.count_in(0, 4), u32::MAX);
+        assert_eq!(counter.count_in_u64(4, 4), 0);
+    }
+
+    #[test]
+    fn test_total_and_is_empty() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert!(counter.is_empty());
+        assert_eq!(counter.total(), 0);
+
+        // A registration at time 0 isn't mistaken for an empty slot
+        counter.register(0);
+        assert!(!counter.is_empty());
+        assert_eq!(counter.total(), 1);
+
+        counter.register_n(12, u32::MAX);
+        counter.register_n(20, 5);
+        assert_eq!(counter.total(), u32::MAX as u64 + 6);
+        assert_eq!(counter.total(), counter.count_in_u64(0, u64::MAX));
+
+        // [0, 8) and [8, 16) age out without their slots being reused
+        counter.advance_time(40);
+        assert_eq!(counter.total(), 5);
+        counter.advance_time(60);
+        assert_eq!(counter.total(), 0);
+        assert!(counter.is_empty());
+    }
+
+    #[test]
+    fn test_count_in_rounded() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 9, 10, 17, 25] {
+            counter.register(time);
+        }
+
+        // Partial slots at both ends: [2, 20) overlaps [0, 24) but only contains [8, 16)
+        assert_eq!(counter.count_in_rounded(2, 20, RoundMode::Outward), 4);
+        assert_eq!(counter.count_in_rounded(2, 20, RoundMode::Inward), 2);
+        // Within a single slot
+        assert_eq!(counter.count_in_rounded(9, 11, RoundMode::Outward), 2);
+        assert_eq!(counter.count_in_rounded(9, 11, RoundMode::Inward), 0);
+        // Aligned bounds are the same in both modes
+        for (start, end) in [(0, 32), (8, 16), (8, 8)] {
+            assert_eq!(
+                counter.count_in_rounded(start, end, RoundMode::Inward),
+                counter.count_in(start, end)
+            );
+        }
+        for (start, end) in [(0, 40), (3, 30), (16, 17), (20, 10)] {
+            assert_eq!(
+                counter.count_in_rounded(start, end, RoundMode::Outward),
+                counter.count_in(start, end)
+            );
+        }
+        // Rounding up doesn't wrap near the end of the u64 range
+        assert_eq!(
+            counter.count_in_rounded(u64::MAX - 1, u64::MAX, RoundMode::Inward),
+            0
+        );
+    }
+
+    #[test]
+    fn test_generic_timestamps() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert!(counter.register_t(1u8));
+        assert!(counter.register_t(9u32));
+        assert!(counter.register_t(10usize));
+        assert!(counter.register_t(17i64));
+        assert!(counter.register_t(25u128));
+        assert!(!counter.register_t(-1i32));
+        assert!(!counter.register_t(u64::MAX as u128 + 1));
+        assert_eq!(counter.lifetime_count(), 5);
+
+        assert_eq!(counter.count_in_t(8u16, 16u16), 2);
+        assert_eq!(counter.count_in_t(-100i64, 16), 3);
+        assert_eq!(counter.count_in_t(i64::MIN, i64::MAX), 5);
+        assert_eq!(counter.count_in_t(16u128, u128::MAX), 2);
+        assert_eq!(counter.count_in_t(-10i32, -1i32), 0);
+    }
+
+    #[test]
+    fn test_count_range() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 9, 10, 17, 30] {
+            counter.register(time);
+        }
+
+        for (start, end) in [(0, 8), (8, 16), (0, 32), (9, 17), (16, 16), (20, 10)] {
+            assert_eq!(
+                counter.count_range(start..end),
+                counter.count_in(start, end)
+            );
+            assert_eq!(
+                counter.count_range(start..=end),
+                counter.count_in(start, end + 1)
+            );
+        }
+        assert_eq!(counter.count_range(..), 5);
+        assert_eq!(counter.count_range(..16), 3);
+        assert_eq!(counter.count_range(..=16), 4);
+        assert_eq!(counter.count_range(16..), 2);
+        assert_eq!(
+            counter.count_range((Bound::Excluded(7), Bound::Unbounded)),
+            counter.count_in(8, 31)
+        );
+        assert_eq!(counter.count_range(0..=u64::MAX), 5);
+    }
+
+    #[test]
+    fn test_rate_in() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        counter.register_n(1, 6);
+        counter.register_n(30, 100);
+
+        assert_eq!(counter.rate_in(0, 4), 1.5);
+        assert_eq!(counter.rate_in(0, 32), 106.0 / 32.0);
+        // The count is taken over [0, 4), but divided by the requested length
+        assert_eq!(counter.rate_in(0, 2), 3.0);
+        assert_eq!(counter.rate_in(4, 28), 0.0);
+        assert_eq!(counter.rate_in(8, 8), 0.0);
+        assert_eq!(counter.rate_in(9, 8), 0.0);
+        // Beyond u32::MAX in total, the rate doesn't saturate
+        counter.register_n(10, u32::MAX);
+        counter.register_n(14, u32::MAX);
+        assert_eq!(counter.rate_in(8, 16), u32::MAX as f64 / 4.0);
+    }
+
+    #[test]
+    fn test_peak_interval() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(counter.peak_interval(), None);
+
+        counter.register_n(1, 9);
+        counter.register_n(10, 2);
+        counter.register_n(20, 5);
+        assert_eq!(counter.peak_interval(), Some((0, 9)));
+
+        // [0, 8) leaves the window, although its slot isn't reused yet
+        counter.advance_time(40);
+        assert_eq!(counter.peak_interval(), Some((16, 5)));
+
+        // Ties break toward the earlier interval, whatever the slot order
+        counter.register_n(41, 5);
+        counter.register_n(24, 5);
+        assert_eq!(counter.peak_interval(), Some((16, 5)));
+        counter.register(42);
+        assert_eq!(counter.peak_interval(), Some((40, 6)));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(counter.snapshot(), vec![]);
+
+        for time in [30, 1, 2, 17, 18, 19] {
+            counter.register(time);
+        }
+        assert_eq!(counter.snapshot(), vec![(0, 2), (16, 3), (24, 1)]);
+
+        // [0, 8) leaves the window and [32, 40) reuses its slot, [8, 16) stays empty
+        counter.register(33);
+        assert_eq!(counter.snapshot(), vec![(16, 3), (24, 1), (32, 1)]);
+        // Stale slots are skipped even before being reused
+        counter.advance_time(50);
+        assert_eq!(counter.snapshot(), vec![(24, 1), (32, 1)]);
+    }
+
+    #[test]
+    fn test_clone() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 9, 10, 25] {
+            counter.register(time);
+        }
+
+        let clone = counter.clone();
+        assert_eq!(clone.snapshot(), counter.snapshot());
+        assert_eq!(clone.count(), 4);
+        assert_eq!(clone.lifetime_count(), 4);
+
+        // The copies evolve independently
+        counter.register(40);
+        clone.register(26);
+        assert_eq!(counter.snapshot(), vec![(24, 1), (40, 1)]);
+        assert_eq!(clone.snapshot(), vec![(0, 1), (8, 2), (24, 2)]);
+    }
+
+    #[test]
+    fn test_display() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(
+            counter.to_string(),
+            "4 slots × 8 = 32 units, latest 0, total 0: []"
+        );
+
+        for time in [20, 1, 0, 9, 30, 31] {
+            counter.register(time);
+        }
+        assert_eq!(
+            counter.to_string(),
+            "4 slots × 8 = 32 units, latest 31, total 6: [0:2, 8:1, 16:1, 24:2]"
+        );
+
+        counter.advance_time(45);
+        assert_eq!(
+            counter.to_string(),
+            "4 slots × 8 = 32 units, latest 45, total 3: [16:1, 24:2]"
+        );
+    }
+
+    #[test]
+    fn test_reset_before() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 2, 9, 17, 18, 25] {
+            counter.register(time);
+        }
+
+        // [0, 8) and [8, 16) start before 16, the boundary of 20
+        counter.reset_before(20);
+        assert_eq!(counter.count_in(0, 16), 0);
+        assert_eq!(counter.count_in(16, 24), 2);
+        assert_eq!(counter.count_in(0, 32), 3);
+        assert_eq!(counter.snapshot(), vec![(16, 2), (24, 1)]);
+        // Only slots are affected
+        assert_eq!(counter.lifetime_count(), 6);
+        counter.register(3);
+        assert_eq!(counter.count_in(0, 8), 1);
+
+        counter.reset_before(0);
+        assert_eq!(counter.count_in(0, 32), 4);
+        counter.reset_before(u64::MAX);
+        assert!(counter.is_empty());
+        assert_eq!(
+            counter.to_string(),
+            "4 slots × 8 = 32 units, latest 25, total 0: []"
+        );
+    }
+
+    #[test]
+    fn test_replace_slot_size() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let mut counter = InvocationCounter::new(3, 2);
+        for t in [0, 1, 5, 9, 10, 14, 20, 27, 31] {
+            counter.register(t);
+        }
+        assert_eq!(counter.count_in(0, 32), 9);
+
+        // Coarser: 8 slots * 8 time units = 64 time units window
+        let previous = counter.replace_slot_size(3);
+        assert_eq!(previous.slot_size_exp(), 2);
+        assert_eq!(previous.count_in(0, 32), 9);
+
+        assert_eq!(counter.slot_size_exp(), 3);
+        assert_eq!(counter.count_in(0, 32), 9);
+        assert_eq!(counter.count_in(0, 8), 3);
+        assert_eq!(counter.count_in(8, 16), 3);
+        assert_eq!(counter.count_in(16, 24), 1);
+        assert_eq!(counter.count_in(24, 32), 2);
+        assert_eq!(counter.lifetime_count(), 9);
+
+        // Finer again: each count lands at the start of its old interval
+        counter.replace_slot_size(2);
+        assert_eq!(counter.count_in(0, 32), 9);
+        assert_eq!(counter.count_in(0, 4), 3);
+        assert_eq!(counter.count_in(4, 8), 0);
+        assert_eq!(counter.count_in(8, 12), 3);
+        assert_eq!(counter.count_in(24, 28), 2);
+
+        // Finer with a window too small for the data drops the oldest intervals
+        // 8 slots * 2 time units = 16 time units window, ending at 31
+        counter.replace_slot_size(1);
+        assert_eq!(counter.count_in(0, 32), 3);
+        assert_eq!(counter.count_in(16, 32), 3);
+        assert_eq!(counter.lifetime_count(), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "slot_size_exp must be below 64")]
+    fn test_replace_slot_size_invalid() {
+        let mut counter = InvocationCounter::new(3, 2);
+        counter.replace_slot_size(64);
+    }
+
+    #[test]
+    fn test_reset_for() {
+        let mut counter = InvocationCounter::new(3, 4);
+        counter.register(10);
+        counter.register(300);
+        let original_slots = counter.slots.as_ptr();
+
+        // Same slot count: the allocation is reused
+        counter.reset_for(3, 2);
+        assert_eq!(counter.slots.as_ptr(), original_slots);
+        assert_eq!(counter.slot_count_exp(), 3);
+        assert_eq!(counter.slot_size_exp(), 2);
+        assert_eq!(counter.count_in(0, 1_000), 0);
+        assert_eq!(counter.lifetime_count(), 0);
+
+        // The reconfigured geometry is in effect: 8 slots * 4 time units
+        counter.register(1);
+        counter.register(5);
+        assert_eq!(counter.count_in(0, 4), 1);
+        assert_eq!(counter.count_in(4, 8), 1);
+
+        // Different slot count: a new allocation is made
+        counter.reset_for(4, 2);
+        assert_ne!(counter.slots.as_ptr(), original_slots);
+        assert_eq!(counter.slots.len(), 16);
+        assert_eq!(counter.slot_count_exp(), 4);
+        assert_eq!(counter.count_in(0, 1_000), 0);
+
+        // Invalid geometries are rejected on both paths and leave the counter untouched
+        counter.register(1);
+        assert!(counter.try_reset_for(4, 64).is_err());
+        assert!(counter.try_reset_for(3, 64).is_err());
+        assert_eq!(counter.slot_size_exp(), 2);
+        assert_eq!(counter.count_in(0, 4), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "reset_for(4, 64)")]
+    fn test_reset_for_invalid_geometry() {
+        let mut counter = InvocationCounter::new(4, 2);
+        counter.reset_for(4, 64);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_count_in_system() {
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let unit = Duration::from_millis(1);
+        // 16 slots (2^4) * 64 ms (2^6) = 1024 ms window
+        let counter = InvocationCounter::new(4, 6);
+
+        for ms in [10, 70, 500, 900] {
+            counter.register(ms);
+        }
+
+        let at = |ms: u64| epoch + Duration::from_millis(ms);
+        for (start, end) in [(0, 1000), (64, 128), (70, 71), (100, 600), (901, 950)] {
+            assert_eq!(
+                counter.count_in_system(at(start), at(end), epoch, unit),
+                counter.count_in(start, end) as u64,
+            );
+        }
+
+        // Sub-unit precision is truncated
+        let start = epoch + Duration::from_micros(64_999);
+        let end = epoch + Duration::from_micros(127_999);
+        assert_eq!(
+            counter.count_in_system(start, end, epoch, unit),
+            counter.count_in(64, 127) as u64
+        );
+
+        // Times before the epoch are clamped to it
+        let before_epoch = epoch - Duration::from_secs(10);
+        assert_eq!(
+            counter.count_in_system(before_epoch, at(64), epoch, unit),
+            1
+        );
+        assert_eq!(
+            counter.count_in_system(before_epoch, before_epoch, epoch, unit),
+            0
+        );
+    }
+
+    #[test]
+    fn test_max_advance_per_register() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3).with_max_advance_per_register(100);
+
+        // A fresh counter is capped from time 0
+        assert!(!counter.try_register(u64::MAX));
+        assert!(!counter.try_register(10_000));
+        assert_eq!(counter.max_time(), 0);
+
+        // Absolute timestamps need a baseline first
+        counter.advance_time(9_950);
+        assert!(counter.try_register(10_000));
+        assert!(!counter.try_register(u64::MAX));
+        assert!(!counter.try_register(10_101));
+        assert!(counter.try_register(10_100));
+
+        // Rejected registrations don't advance the window
+        assert_eq!(counter.count_in(10_100, 10_101), 1);
+        assert_eq!(counter.lifetime_count(), 2);
+
+        // Plain `register` is bounded by the same cap
+        counter.register(u64::MAX);
+        assert_eq!(
+            counter.register_n_observed(u64::MAX, 3),
+            RegisterOutcome {
+                added: 0,
+                evicted: 0
+            }
+        );
+        assert_eq!(counter.lifetime_count(), 2);
+
+        // Subsequent normal registrations still count
+        counter.register(10_110);
+        counter.register(10_111);
+        assert_eq!(counter.count_in(10_100, 10_120), 3);
+
+        // Without a cap nothing is rejected
+        let uncapped = InvocationCounter::new(2, 3);
+        uncapped.register(10);
+        assert!(uncapped.try_register(u64::MAX - 1));
+    }
+
+    #[test]
+    fn test_slot_count_mask_matches_modulo() {
+        for slot_count_exp in 0..=10 {
+            let counter = InvocationCounter::new(slot_count_exp, 0);
+            let slot_count = 1u64 << slot_count_exp;
+            assert_eq!(counter.slot_count_mask + 1, slot_count);
+
+            for interval in (0..5_000).chain(u64::MAX - 5_000..=u64::MAX) {
+                assert_eq!(interval & counter.slot_count_mask, interval % slot_count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_register_at_matches_register() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let with_register = InvocationCounter::new(3, 2);
+        let with_register_at = InvocationCounter::new(3, 2);
+
+        for time in [0, 1, 3, 4, 9, 15, 16, 30, 33, 47, 47, 100, 101, 96] {
+            with_register.register(time);
+            with_register_at.register_at(time & !3, time & 3).unwrap();
+        }
+
+        for start in 0..110 {
+            for end in start..110 {
+                assert_eq!(
+                    with_register.count_in(start, end),
+                    with_register_at.count_in(start, end)
+                );
+            }
+        }
+        assert_eq!(
+            with_register.lifetime_count(),
+            with_register_at.lifetime_count()
+        );
+    }
+
+    #[test]
+    fn test_register_at_validation() {
+        let counter = InvocationCounter::new(3, 2);
+
+        assert_eq!(
+            counter.register_at(8, 4),
+            Err(CounterError::InvalidArgument {
+                reason: "offset_in_slot is not smaller than the slot size"
+            })
+        );
+        assert_eq!(
+            counter.register_at(9, 0),
+            Err(CounterError::InvalidArgument {
+                reason: "interval_start is not aligned to a slot boundary"
+            })
+        );
+        assert_eq!(counter.lifetime_count(), 0);
+
+        // Single-unit slots only accept an offset of 0
+        let counter = InvocationCounter::new(3, 0);
+        assert!(counter.register_at(7, 0).is_ok());
+        assert!(counter.register_at(7, 1).is_err());
+    }
+
+    /// The observable state of a counter touched by the clearing methods.
+    fn clearing_state(counter: &InvocationCounter) -> (bool, u64, u64, u64) {
+        let slots_empty = counter
+            .slots
+            .iter()
+            .all(|slot| slot.counter.load(Ordering::Acquire) == 0);
+        (
+            slots_empty,
+            counter.max_current_time.load(Ordering::Acquire),
+            counter.lifetime_count(),
+            counter.spillover(),
+        )
+    }
+
+    fn populated_counter() -> InvocationCounter {
+        let counter = InvocationCounter::new(2, 3);
+        counter.register(5);
+        counter.register_n_observed(20, u32::MAX);
+        counter.register(21);
+        counter
+    }
+
+    #[test]
+    fn test_clearing_methods() {
+        let lifetime = u32::MAX as u64 + 2;
+
+        let counter = populated_counter();
+        assert_eq!(clearing_state(&counter), (false, 21, lifetime, 1));
+
+        // clear: slots, latest time and spillover
+        let counter = populated_counter();
+        counter.clear();
+        assert_eq!(clearing_state(&counter), (true, 0, lifetime, 0));
+        assert_eq!(counter.count_in(0, 32), 0);
+
+        // reset: same as clear
+        let counter = populated_counter();
+        counter.reset();
+        assert_eq!(clearing_state(&counter), (true, 0, lifetime, 0));
+
+        // reset_lifetime: lifetime total only
+        let counter = populated_counter();
+        counter.reset_lifetime();
+        assert_eq!(clearing_state(&counter), (false, 21, 0, 1));
+
+        // reset_for: everything
+        let mut counter = populated_counter();
+        counter.reset_for(2, 3);
+        assert_eq!(clearing_state(&counter), (true, 0, 0, 0));
+
+        // A cleared counter works like a new one
+        let counter = populated_counter();
+        counter.clear();
+        counter.register(3);
+        assert_eq!(counter.count_in(0, 8), 1);
+        assert_eq!(counter.count_in(16, 24), 0);
+    }
+
+    #[test]
+    fn test_count_in_billing_adjacent_windows() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let counter = InvocationCounter::new(4, 2);
+        for time in 0..64 {
+            counter.register(time);
+        }
+
+        let boundaries = [0, 1, 7, 13, 20, 33, 34, 35, 50, 63];
+        let per_window: Vec<u64> = boundaries
+            .windows(2)
+            .map(|w| counter.count_in_billing(w[0], w[1]))
+            .collect();
+
+        // No overlap and no gap: the windows add up to the covering window
+        assert_eq!(
+            per_window.iter().sum::<u64>(),
+            counter.count_in_billing(0, 63)
+        );
+        assert_eq!(counter.count_in_billing(0, 63), 64);
+
+        // Intervals are attributed by their last time unit
+        assert_eq!(counter.count_in_billing(0, 1), 0);
+        assert_eq!(counter.count_in_billing(1, 7), 8); // [0, 4) and [4, 8)
+        assert_eq!(counter.count_in_billing(7, 13), 4); // [8, 12)
+        assert_eq!(counter.count_in_billing(13, 20), 8); // [12, 16) and [16, 20)
+        assert_eq!(counter.count_in_billing(33, 34), 0);
+        assert_eq!(counter.count_in_billing(34, 35), 4); // [32, 36)
+        assert_eq!(counter.count_in_billing(35, 36), 0);
+
+        // Empty and inverted windows
+        assert_eq!(counter.count_in_billing(10, 10), 0);
+        assert_eq!(counter.count_in_billing(20, 10), 0);
+    }
+
+    #[test]
+    fn test_count_in_billing_single_unit_slots() {
+        // 8 slots (2^3) * 1 time unit (2^0) = 8 time units window
+        let counter = InvocationCounter::new(3, 0);
+        for time in 0..8 {
+            counter.register(time);
+        }
+
+        // With single-unit slots the semantics are exact
+        assert_eq!(counter.count_in_billing(2, 5), 3); // 3, 4, 5
+        assert_eq!(counter.count_in_billing(0, 7), 7); // 1..=7
+    }
+
+    #[test]
+    fn test_slot_index_invariant() {
+        // Every registration goes through the `debug_assert` on the slot index: run many of
+        // them across geometries, including after reconfiguring, and check the invariant the
+        // unchecked access relies on.
+        let mut counter = InvocationCounter::new(0, 0);
+        for (slot_count_exp, slot_size_exp) in [(0, 0), (1, 0), (3, 2), (3, 7), (8, 1), (2, 63)] {
+            counter.reset_for(slot_count_exp, slot_size_exp);
+            assert_eq!(counter.slots.len() as u64, counter.slot_count_mask + 1);
+
+            let mut time = 0u64;
+            for i in 0..10_000u64 {
+                time = time.wrapping_add(i.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+                counter.register(time);
+            }
+            counter.register(u64::MAX);
+        }
+    }
+
+    #[test]
+    fn test_grouped_count_in() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let counter = InvocationCounter::new(4, 2);
+        // Interval i (starting at 4 * i) gets i + 1 invocations
+        for i in 0..16u64 {
+            counter.register_n_observed(i * 4, i as u32 + 1);
+        }
+
+        // Evenly divisible: 16 intervals in 4 groups of 4
+        assert_eq!(
+            counter.grouped_count_in(0, 64, 4),
+            vec![
+                1 + 2 + 3 + 4,
+                5 + 6 + 7 + 8,
+                9 + 10 + 11 + 12,
+                13 + 14 + 15 + 16
+            ]
+        );
+        // One group is the plain count
+        assert_eq!(
+            counter.grouped_count_in(0, 64, 1),
+            vec![counter.count_in(0, 64) as u64]
+        );
+
+        // Not divisible: 5 intervals ([4, 24)) in groups of 3 and 2 intervals
+        assert_eq!(counter.grouped_count_in(5, 23, 2), vec![2 + 3 + 4, 5 + 6]);
+
+        // More groups than intervals: the extra groups stay empty
+        assert_eq!(counter.grouped_count_in(0, 8, 4), vec![1, 0, 2, 0]);
+
+        // Degenerate inputs
+        assert_eq!(counter.grouped_count_in(0, 64, 0), Vec::<u64>::new());
+        assert_eq!(counter.grouped_count_in(10, 10, 3), vec![0, 0, 0]);
+
+        // Near `u64::MAX`, where the aligned end saturates
+        let counter = InvocationCounter::new(3, 4);
+        counter.register(u64::MAX - 5);
+        assert_eq!(
+            counter.grouped_count_in(u64::MAX - 10, u64::MAX, 1),
+            vec![1]
+        );
+        assert_eq!(counter.grouped_count_in(0, u64::MAX, 4), vec![0, 0, 0, 1]);
+        assert_eq!(
+            counter.grouped_count_in(u64::MAX - 40, u64::MAX, 3),
+            vec![0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_tiered_counts() {
+        // 32 slots (2^5) * 4 time units (2^2) = 128 time units window
+        let counter = InvocationCounter::new(5, 2);
+        // One invocation per time unit over [0, 128)
+        for time in 0..128 {
+            counter.register(time);
+        }
+
+        // Intervals starting at 100 (age 0) down to 92 (age 8), 88 to 72 (ages 12 to 28),
+        // then 68 to 40 (ages 32 to 60)
+        assert_eq!(
+            counter.tiered_counts(100, &[10, 30, 60]),
+            Ok(vec![3 * 4, 5 * 4, 8 * 4])
+        );
+        // The interval starting at `now` is fully counted, later intervals aren't
+        assert_eq!(counter.tiered_counts(100, &[0]), Ok(vec![4]));
+        assert_eq!(counter.tiered_counts(101, &[0]), Ok(vec![0]));
+        // Ages beyond the window only count what's still held
+        assert_eq!(counter.tiered_counts(127, &[1_000]), Ok(vec![128]));
+
+        assert_eq!(counter.tiered_counts(100, &[]), Ok(vec![]));
+        assert_eq!(
+            counter.tiered_counts(100, &[30, 10]),
+            Err(CounterError::InvalidQuery {
+                reason: "tier boundaries must be strictly ascending",
+            })
+        );
+        assert!(counter.tiered_counts(100, &[10, 10]).is_err());
+    }
+
+    #[test]
+    fn test_count_in_with_scratch() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let counter = InvocationCounter::new(4, 2);
+        let mut scratch = Vec::new();
+        for time in 0..200 {
+            counter.register(time);
+        }
+
+        assert_eq!(counter.count_in_with_scratch(136, 200, &mut scratch), 64);
+        let capacity = scratch.capacity();
+        let buffer = scratch.as_ptr();
+        assert!(capacity >= 16);
+
+        for (start, end) in [(150, 180), (0, 100), (190, 300), (170, 170), (136, 200)] {
+            let count = counter.count_in_with_scratch(start, end, &mut scratch);
+            assert_eq!(count, counter.count_in(start, end) as u64);
+            assert_eq!(scratch.len(), count as usize / 4);
+            assert!(scratch.iter().all(|&(_, count)| count == 4));
+
+            // No reallocation after the first call
+            assert_eq!(scratch.capacity(), capacity);
+            assert_eq!(scratch.as_ptr(), buffer);
+        }
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register(1);
+        assert!(!counter.is_paused());
+
+        counter.pause();
+        assert!(counter.is_paused());
+        assert!(!counter.try_register(2));
+        counter.register(3);
+        assert_eq!(counter.register_n_observed(4, 10).added, 0);
+
+        // Queries still work during the pause, and nothing was recorded
+        assert_eq!(counter.count_in(0, 8), 1);
+        assert_eq!(counter.lifetime_count(), 1);
+
+        // By default a pause freezes the window too
+        counter.register(1_000);
+        assert_eq!(counter.count_in(0, 8), 1);
+
+        counter.resume();
+        assert!(counter.try_register(5));
+        counter.register(6);
+        assert_eq!(counter.count_in(0, 8), 3);
+    }
+
+    #[test]
+    fn test_time_advance_while_paused() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let mut counter = InvocationCounter::new(2, 3).with_time_advance_while_paused(true);
 
-        counter.register(0);
         counter.register(1);
-        counter.register(8);
-        counter.register(16);
+        counter.pause();
+        counter.register(1_000);
 
-        // All within 32-unit window from time 16
-        assert_eq!(counter.count_in(0, 16 + 1), 4);
+        // The dropped registration moved the window past the recorded one
+        assert_eq!(counter.count_in(0, 8), 0);
+        assert_eq!(counter.count_in(992, 1_000), 0);
+        assert_eq!(counter.lifetime_count(), 1);
 
-        // All outside window from time 100
-        assert_eq!(counter.count_in(100 - 32, 100 + 1), 0);
+        // Pause state and settings survive reconfiguration
+        counter.reset_for(3, 3);
+        assert!(counter.is_paused());
+        assert!(counter.advance_time_while_paused);
     }
 
     #[test]
-    fn test_count_in() {
-        // 2 slots (2^1) * 4 time units (2^2) = 8 time units window
-        // Slot 0: times 0-3 (interval_start = 0)
-        // Slot 1: times 4-7 (interval_start = 4)
-        let counter = InvocationCounter::new(1, 2);
+    fn test_saturating_window_size() {
+        assert_eq!(InvocationCounter::new(0, 0).saturating_window_size(), 1);
+        assert_eq!(InvocationCounter::new(2, 3).saturating_window_size(), 32);
+        assert_eq!(
+            InvocationCounter::new(1, 62).saturating_window_size(),
+            1 << 63
+        );
+        // 4 slots (2^2) * 2^62 time units = 2^64: doesn't fit in a u64
+        let counter = InvocationCounter::new(2, 62);
+        assert_eq!(counter.saturating_window_size(), u64::MAX);
 
+        // Everything since time 0 stays in the window
         counter.register(0);
+        counter.register(3 << 62);
+        assert_eq!(counter.count_in(0, 1), 1);
+        assert_eq!(counter.count_in(0, 1 << 62), 1);
+        assert_eq!(counter.count_in(0, 3 << 62), 1);
+        assert_eq!(counter.count_in(0, u64::MAX), 2);
+    }
 
-        // All registrations 0,1,2,3 go to the same slot (interval_start = 0)
-        // count_in works on slot boundaries, so any range that includes slot 0 gets all counts
-        assert_eq!(counter.count_in(0, 1), 1); // Includes slot 0
-        assert_eq!(counter.count_in(0, 4), 1); // Includes slot 0
-        assert_eq!(counter.count_in(0, 8), 1); // Includes slot 0
-        assert_eq!(counter.count_in(4, 8), 0); // Only includes slot 1 (empty)
+    #[test]
+    fn test_count_in_up_to_u64_max() {
+        // 8 slots (2^3) * 16 time units (2^4) = 128 time units window
+        let counter = InvocationCounter::new(3, 4);
+        let window = counter.window_size();
+        let large = u64::MAX - 5;
 
-        counter.register(1);
-        // Still all in slot 0
-        assert_eq!(counter.count_in(0, 4), 2); // Includes slot 0
-        assert_eq!(counter.count_in(0, 8), 2); // Includes slot 0
-        assert_eq!(counter.count_in(4, 8), 0); // Only slot 1
+        counter.register(large);
+        counter.register(large - 20);
+        assert_eq!(counter.count_in(large - window, u64::MAX), 2);
+        assert_eq!(counter.count_in(large, u64::MAX), 1);
+        assert_eq!(counter.count_in(0, u64::MAX), 2);
+        assert_eq!(counter.count(), 2);
+    }
 
-        counter.register(2);
-        assert_eq!(counter.count_in(0, 4), 3); // Slot 0
-        assert_eq!(counter.count_in(4, 8), 0); // Slot 1
+    #[test]
+    fn test_count_in_complete() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for time in [1, 5, 6, 9] {
+            counter.register(time);
+        }
+        // The current interval [12, 16)
+        counter.register(12);
+        counter.register(14);
+
+        assert_eq!(counter.count_in_complete(0, 14), 4);
+        assert_eq!(counter.count_in(0, 15), 6);
+        // `start_time` is aligned like in `count_in`
+        assert_eq!(counter.count_in_complete(7, 14), 3);
+        // At a slot boundary the new interval hasn't elapsed at all
+        assert_eq!(counter.count_in_complete(0, 12), 4);
+        assert_eq!(counter.count_in_complete(0, 16), 6);
+        // Nothing is complete yet
+        assert_eq!(counter.count_in_complete(12, 14), 0);
+    }
 
+    #[test]
+    fn test_interarrival_histogram() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let counter = InvocationCounter::new(4, 2);
+        assert_eq!(counter.interarrival_histogram(), vec![]);
         counter.register(3);
-        assert_eq!(counter.count_in(0, 4), 4); // Slot 0
-        assert_eq!(counter.count_in(4, 8), 0); // Slot 1
+        assert_eq!(counter.interarrival_histogram(), vec![]);
 
-        // Register 4 in slot 1 (interval_start = 4)
-        counter.register(4);
-        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only (end_time=4 excludes slot 1)
-        assert_eq!(counter.count_in(4, 8), 1); // Slot 1 only
-        assert_eq!(counter.count_in(0, 8), 5); // Both slots
+        // Regularly spaced, several invocations per interval
+        for time in (0..64).step_by(8) {
+            counter.register(time);
+            counter.register(time + 1);
+        }
+        assert_eq!(counter.interarrival_histogram(), vec![(8, 7)]);
 
-        counter.register(5);
-        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
-        assert_eq!(counter.count_in(4, 8), 2); // Slot 1 only
-        assert_eq!(counter.count_in(0, 8), 6); // Both slots
+        // Irregularly spaced: 64, 68, 80, 104 and 108; the window is now [48, 112)
+        let irregular = InvocationCounter::new(4, 2);
+        for time in [64, 69, 80, 104, 111] {
+            irregular.register(time);
+        }
+        assert_eq!(
+            irregular.interarrival_histogram(),
+            vec![(4, 2), (12, 1), (24, 1)]
+        );
 
-        counter.register(6);
-        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
-        assert_eq!(counter.count_in(4, 8), 3); // Slot 1 only
-        assert_eq!(counter.count_in(0, 8), 7); // Both slots
+        // Evicted intervals don't count: the window is now [28, 92)
+        counter.register(90);
+        assert_eq!(counter.interarrival_histogram(), vec![(8, 3), (32, 1)]);
+    }
 
-        counter.register(7);
-        assert_eq!(counter.count_in(0, 4), 4); // Slot 0 only
-        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 only
-        assert_eq!(counter.count_in(0, 8), 8); // Both slots
-        assert_eq!(counter.count_in(4, 12), 4); // Slot 1 only
-        assert_eq!(counter.count_in(12, 16), 0); // Out of range
+    #[test]
+    fn test_is_in_window_matches_count_in() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [0, 9, 17, 30, 41, 50, 70] {
+            counter.register(time);
 
-        // Register 8 - wraps to slot 0, resets it (interval_start = 8)
-        counter.register(8);
-        assert_eq!(counter.count_in(0, 4), 0); // Old slot 0 data gone
-        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
-        assert_eq!(counter.count_in(8, 12), 1); // New slot 0
-        assert_eq!(counter.count_in(4, 12), 5); // Slot 1 + new slot 0
-        assert_eq!(counter.count_in(8, 9), 1); // Within new slot 0
-        assert_eq!(counter.count_in(0, 12), 5); // Full range
+            for interval_start in (0..96).step_by(8) {
+                let counted = counter.count_in(interval_start, interval_start + 8) > 0;
+                let registered = [0, 9, 17, 30, 41, 50, 70]
+                    .iter()
+                    .any(|&t| t <= time && t >> 3 << 3 == interval_start);
+                assert_eq!(
+                    counted,
+                    registered && counter.is_in_window(interval_start),
+                    "interval {interval_start} after registering {time}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_strategies_agree() {
+        // 64 slots (2^6) * 4 time units (2^2) = 256 time units window
+        let counter = InvocationCounter::new(6, 2);
+        // Deterministic xorshift, to avoid a dependency on an RNG
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for time in 0..2_000 {
+            if next() % 3 == 0 {
+                counter.register(time);
+            }
+        }
+
+        fn collect(scan: impl FnOnce(&mut dyn FnMut(u64, u32))) -> Vec<(u64, u32)> {
+            let mut seen = Vec::new();
+            scan(&mut |interval_start, count| {
+                if count > 0 {
+                    seen.push((interval_start, count));
+                }
+            });
+            seen.sort_unstable();
+            seen
+        }
+
+        for _ in 0..1_000 {
+            let start = next() % 2_200;
+            let end = start + next() % 400;
+            let valid_range = counter.valid_range(start, end);
+            if valid_range.is_empty() {
+                continue;
+            }
+            assert_eq!(
+                collect(|f| counter.scan_full(valid_range.clone(), f)),
+                collect(|f| counter.scan_indexed(valid_range.clone(), f)),
+                "[{start}, {end})"
+            );
+        }
+    }
 
+    #[test]
+    fn test_register_too_old() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert!(counter.try_register(5));
+        counter.advance_time(36);
+
+        // The window is [8, 40): [0, 8) maps to the slot now free for [32, 40)
+        assert!(!counter.try_register(5));
+        assert!(counter.try_register(8));
+        assert!(counter.try_register(35));
+        assert_eq!(counter.count_in(32, 40), 1);
+
+        // Dropping doesn't clobber the slot holding the newer interval
+        counter.register(2);
+        counter.register_n(6, 10);
+        assert_eq!(counter.count_in(32, 40), 1);
+        assert_eq!(counter.count_in(0, 8), 0);
+        assert_eq!(counter.lifetime_count(), 3);
+        assert_eq!(counter.snapshot(), vec![(8, 1), (32, 1)]);
+    }
+
+    #[test]
+    fn test_register_max() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+
+        counter.register_max(10, 5);
+        counter.register_max(10, 5);
+        assert_eq!(counter.count_in(8, 16), 5);
+        assert_eq!(counter.lifetime_count(), 5);
+
+        // Only raises the interval, by the difference
+        counter.register_max(12, 3);
+        assert_eq!(counter.count_in(8, 16), 5);
+        counter.register_max(15, 7);
+        assert_eq!(counter.count_in(8, 16), 7);
+        assert_eq!(counter.lifetime_count(), 7);
+
+        // Mixes with additive registration
         counter.register(9);
-        assert_eq!(counter.count_in(0, 4), 0); // Old slot 0 still gone
-        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
-        assert_eq!(counter.count_in(8, 12), 2); // New slot 0 has 2 entries
-        assert_eq!(counter.count_in(4, 12), 6); // Slot 1 + new slot 0
-        assert_eq!(counter.count_in(8, 10), 2); // Within new slot 0
-        assert_eq!(counter.count_in(0, 16), 6); // Full range
+        assert_eq!(counter.count_in(8, 16), 8);
+
+        // Rolls a slot over like a registration
+        counter.register_max(42, 2);
+        assert_eq!(counter.count_in(8, 16), 0);
+        assert_eq!(counter.count_in(40, 48), 2);
+    }
+
+    #[test]
+    fn test_generation() {
+        let mut counter = InvocationCounter::new(3, 4);
+        let other = InvocationCounter::new(3, 4);
+
+        let mut last = counter.generation();
+        let mut assert_bumped = |counter: &InvocationCounter| {
+            let generation = counter.generation();
+            assert!(generation > last, "{generation} should be above {last}");
+            last = generation;
+        };
 
         counter.register(10);
-        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
-        assert_eq!(counter.count_in(8, 12), 3); // New slot 0 has 3 entries
-        assert_eq!(counter.count_in(4, 12), 7); // Slot 1 + new slot 0
-        assert_eq!(counter.count_in(8, 11), 3); // Within new slot 0
-        assert_eq!(counter.count_in(0, 16), 7); // Full range
+        assert_bumped(&counter);
+        counter.register_n_observed(20, 5);
+        assert_bumped(&counter);
+        counter.register_at(32, 1).unwrap();
+        assert_bumped(&counter);
+        counter.advance_time(100);
+        assert_bumped(&counter);
+        counter.reset_lifetime();
+        assert_bumped(&counter);
+        counter.clear();
+        assert_bumped(&counter);
+        counter.swap_contents(&other).unwrap();
+        assert_bumped(&counter);
+        counter.replace_slot_size(5);
+        assert_bumped(&counter);
+        counter.reset_for(3, 4);
+        assert_bumped(&counter);
+        counter.reset_for(4, 4);
+        assert_bumped(&counter);
 
-        counter.register(11);
-        assert_eq!(counter.count_in(4, 8), 4); // Slot 1 unchanged
-        assert_eq!(counter.count_in(8, 12), 4); // New slot 0 has 4 entries
-        assert_eq!(counter.count_in(4, 12), 8); // Slot 1 + new slot 0
-        assert_eq!(counter.count_in(8, 12), 4); // Full new slot 0
-        assert_eq!(counter.count_in(10, 12), 4); // Includes full slot 0 (slot-boundary aligned)
-        assert_eq!(counter.count_in(0, 16), 8); // Full range
+        // Pure queries and no-op mutations leave it unchanged
+        counter.register(200);
+        let generation = counter.generation();
+        counter.count_in(0, 256);
+        counter.count_in_saturating(0, 256);
+        counter.grouped_count_in(0, 256, 4);
+        counter.active_intervals_in(0, 256);
+        counter.lifetime_count();
+        counter.advance_time(150); // Earlier than the latest time
+        counter.pause();
+        counter.register(210); // Rejected while paused
+        assert_eq!(counter.generation(), generation);
+    }
 
-        // Register 12 - wraps to slot 1, resets it (interval_start = 12)
-        counter.register(12);
-        assert_eq!(counter.count_in(4, 8), 0); // Old slot 1 data gone
-        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
-        assert_eq!(counter.count_in(12, 16), 1); // New slot 1
-        assert_eq!(counter.count_in(8, 16), 5); // Slot 0 + new slot 1
-        assert_eq!(counter.count_in(0, 16), 5); // Full range
-        assert_eq!(counter.count_in(12, 13), 1); // Within new slot 1
+    #[test]
+    fn test_count_in_many() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for time in [1, 3, 9, 17, 25, 30, 33, 34, 41, 50] {
+            counter.register(time);
+        }
+        counter.register_n(49, u32::MAX);
 
-        counter.register(13);
-        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
-        assert_eq!(counter.count_in(12, 16), 2); // New slot 1 has 2 entries
-        assert_eq!(counter.count_in(8, 16), 6); // Slot 0 + new slot 1
-        assert_eq!(counter.count_in(0, 16), 6); // Full range
-        assert_eq!(counter.count_in(12, 14), 2); // Within new slot 1
+        let ranges: Vec<_> = (0..64)
+            .flat_map(|start| (0..64).map(move |end| (start, end)))
+            .chain([(0, u64::MAX), (u64::MAX, u64::MAX), (10, 5)])
+            .collect();
+        let expected: Vec<_> = ranges
+            .iter()
+            .map(|&(start, end)| counter.count_in(start, end))
+            .collect();
+        assert_eq!(counter.count_in_many(&ranges), expected);
 
-        counter.register(14);
-        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
-        assert_eq!(counter.count_in(12, 16), 3); // New slot 1 has 3 entries
-        assert_eq!(counter.count_in(8, 16), 7); // Slot 0 + new slot 1
-        assert_eq!(counter.count_in(0, 16), 7); // Full range
-        assert_eq!(counter.count_in(12, 15), 3); // Within new slot 1
+        assert_eq!(counter.count_in_many(&[]), Vec::<u32>::new());
+        assert_eq!(counter.count_in_many(&[(0, 8), (5, 5)]), vec![0, 0]);
+    }
 
-        counter.register(15);
-        assert_eq!(counter.count_in(8, 12), 4); // Slot 0 unchanged
-        assert_eq!(counter.count_in(12, 16), 4); // New slot 1 has 4 entries
-        assert_eq!(counter.count_in(8, 16), 8); // Slot 0 + new slot 1
-        assert_eq!(counter.count_in(0, 16), 8); // Full range
-        assert_eq!(counter.count_in(12, 16), 4); // Full new slot 1
+    #[test]
+    fn test_to_buckets() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        assert_eq!(
+            counter.to_buckets(),
+            vec![Bucket {
+                start: 0,
+                end: 8,
+                count: 0
+            }]
+        );
 
-        // Register 16 - wraps to slot 0 again, resets it (interval_start = 16)
-        counter.register(16);
-        assert_eq!(counter.count_in(8, 12), 0); // Old slot 0 data gone
-        assert_eq!(counter.count_in(12, 16), 4); // Slot 1 unchanged
-        assert_eq!(counter.count_in(16, 20), 1); // New slot 0
-        assert_eq!(counter.count_in(12, 20), 5); // Slot 1 + new slot 0
-        assert_eq!(counter.count_in(0, 20), 5); // Full range
-        assert_eq!(counter.count_in(16, 17), 1); // Within new slot 0
+        for time in [3, 4, 20, 50, 52, 60] {
+            counter.register(time);
+        }
+        let buckets = counter.to_buckets();
+        assert_eq!(
+            buckets
+                .iter()
+                .map(|bucket| (bucket.start, bucket.end, bucket.count))
+                .collect::<Vec<_>>(),
+            vec![(32, 40, 0), (40, 48, 0), (48, 56, 2), (56, 64, 1)]
+        );
+        let total: u32 = buckets.iter().map(|bucket| bucket.count).sum();
+        assert_eq!(total, counter.count());
+
+        // The last interval ends at `u64::MAX`
+        let counter = InvocationCounter::new(2, 3);
+        counter.register(u64::MAX);
+        let buckets = counter.to_buckets();
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(
+            buckets[3],
+            Bucket {
+                start: u64::MAX - 7,
+                end: u64::MAX,
+                count: 1
+            }
+        );
     }
 
     #[test]
-    fn test_slot_reuse() {
-        // 4 slots (2^2) * 4 time units (2^2) = 16 time units window
-        let counter = InvocationCounter::new(2, 2);
+    fn test_ordering_profile() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let strict = InvocationCounter::new(2, 3);
+        let mut fast = InvocationCounter::new(2, 3).with_ordering_profile(OrderingProfile::Fast);
+        assert_eq!(strict.ordering_profile(), OrderingProfile::Strict);
+        assert_eq!(fast.ordering_profile(), OrderingProfile::Fast);
 
-        counter.register(0); // slot 0
-        counter.register(16); // wraps to slot 0, resets counter
+        for time in [1, 3, 9, 17, 25, 30, 33, 34, 41, 50] {
+            strict.register(time);
+            fast.register(time);
+        }
+        strict.register_max(52, 4);
+        fast.register_max(52, 4);
+        for start in 0..64 {
+            for end in start..64 {
+                assert_eq!(fast.count_in(start, end), strict.count_in(start, end));
+            }
+        }
+        assert_eq!(fast.snapshot(), strict.snapshot());
 
-        assert_eq!(counter.count_in(0, 17), 1); // Only recent registration
+        // The profile is a setting: it survives cloning and reconfiguration
+        assert_eq!(fast.clone().ordering_profile(), OrderingProfile::Fast);
+        fast.reset_for(3, 3);
+        assert_eq!(fast.ordering_profile(), OrderingProfile::Fast);
     }
 
     #[test]
-    fn test_concurrent_access() {
-        let num_threads = 4;
-        let registrations_per_thread = 100;
+    fn test_from_snapshot() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 3, 9, 17, 25, 30, 33, 34, 41, 50] {
+            counter.register(time);
+        }
 
-        // 8 slots (2^3) * 64 time units (2^6) = 512 time units window
-        let counter = Arc::new(InvocationCounter::new(3, 6));
+        let restored = InvocationCounter::from_snapshot(2, 3, &counter.snapshot());
+        assert_eq!(restored.snapshot(), counter.snapshot());
+        for start in 0..64 {
+            for end in start..64 {
+                assert_eq!(restored.count_in(start, end), counter.count_in(start, end));
+            }
+        }
+        assert_eq!(restored.max_current_time.load(Ordering::Relaxed), 55);
 
-        let handles: Vec<_> = (0..num_threads)
-            .map(|thread_id| {
-                let counter_clone = Arc::clone(&counter);
-                thread::spawn(move || {
-                    for i in 0..registrations_per_thread {
-                        counter_clone.register(thread_id as u64 * 10 + i as u64);
-                    }
-                })
-            })
-            .collect();
+        // [0, 8) and [32, 40) share slot 0: the later interval wins in any order
+        for entries in [[(0, 1), (32, 2)], [(32, 2), (0, 1)]] {
+            let restored = InvocationCounter::from_snapshot(2, 3, &entries);
+            assert_eq!(restored.snapshot(), vec![(32, 2)]);
+        }
 
-        for handle in handles {
-            handle.join().unwrap();
+        // Duplicated intervals add up, unaligned starts are rounded down
+        let restored = InvocationCounter::from_snapshot(2, 3, &[(8, 1), (12, 2), (8, u32::MAX)]);
+        assert_eq!(restored.snapshot(), vec![(8, u32::MAX)]);
+        assert_eq!(restored.spillover(), 3);
+
+        let empty = InvocationCounter::from_snapshot(2, 3, &[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.max_current_time.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_max_time() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3).with_max_advance_per_register(1_000);
+        assert_eq!(counter.max_time(), 0);
+
+        counter.register(20);
+        counter.register(5);
+        assert_eq!(counter.max_time(), 20);
+
+        counter.register(10_000); // Rejected by the cap
+        assert_eq!(counter.max_time(), 20);
+
+        counter.advance_time(100);
+        assert_eq!(counter.max_time(), 100);
+
+        counter.clear();
+        assert_eq!(counter.max_time(), 0);
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 3, 9, 17, 25] {
+            counter.register(time);
         }
+        assert_eq!(counter, counter);
+        assert_eq!(counter.clone(), counter);
 
-        let count = counter.count_in(0, 399); // Query at end of all registrations
-        assert!(count > 0);
-        assert!(count <= num_threads * registrations_per_thread);
+        // Settings and totals aren't compared
+        let restored = InvocationCounter::from_parts(
+            2,
+            3,
+            25,
+            counter
+                .slots
+                .iter()
+                .map(|slot| slot.load(&ORDERINGS.count_in)),
+        )
+        .unwrap();
+        assert_eq!(restored.lifetime_count(), 0);
+        assert_eq!(restored, counter);
+
+        let other = counter.clone();
+        other.register(26);
+        assert_ne!(other, counter);
+        let other = counter.clone();
+        other.advance_time(26);
+        assert_ne!(other, counter);
+
+        // Same contents, different geometry
+        assert_ne!(InvocationCounter::new(2, 3), InvocationCounter::new(2, 4));
+        assert_ne!(InvocationCounter::new(2, 3), InvocationCounter::new(3, 3));
     }
 
     #[test]
-    fn test_edge_cases() {
+    fn test_unregister() {
         // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
         let counter = InvocationCounter::new(2, 3);
 
-        // Empty counter
-        assert_eq!(counter.count_in(0, 0), 0);
+        // Within the same interval
+        counter.register(1);
+        counter.register(2);
+        counter.register(3);
+        counter.unregister(4);
+        assert_eq!(counter.count_in(0, 8), 2);
+        counter.unregister(5);
+        counter.unregister(6);
+        counter.unregister(7); // Saturates at 0
+        assert_eq!(counter.count_in(0, 8), 0);
+        counter.register(1);
+        assert_eq!(counter.count_in(0, 8), 1);
 
-        // Zero time
-        counter.register(0);
-        assert_eq!(counter.count_in(0, 1), 1);
+        // Never registered into: no new interval, the latest time doesn't move
+        let generation = counter.generation();
+        counter.unregister(20);
+        assert_eq!(counter.snapshot(), vec![(0, 1)]);
+        assert_eq!(counter.max_time(), 3);
+        assert_eq!(counter.generation(), generation);
 
-        // Large time values
-        let large_time = 1_000_000u64;
-        counter.register(large_time);
-        assert_eq!(counter.count_in(large_time, large_time + 1), 1);
+        // Across slot reuse: [32, 40) evicts [0, 8), which can't be unregistered from anymore
+        counter.register(33);
+        counter.register(34);
+        counter.unregister(2);
+        assert_eq!(counter.snapshot(), vec![(32, 2)]);
+        counter.unregister(35);
+        assert_eq!(counter.snapshot(), vec![(32, 1)]);
+        assert_eq!(counter.lifetime_count(), 6);
+
+        counter.pause();
+        counter.unregister(35);
+        assert_eq!(counter.count_in(32, 40), 1);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_advance() {
+        // 8 slots (2^3) * 16 time units (2^4) = 128 time units window
+        let counter = InvocationCounter::new(3, 4);
+        counter.register(10);
+        counter.register(20);
+        assert_eq!(counter.count_last(128), 2);
+
+        counter.advance(100);
+        assert_eq!(counter.count_last(128), 2);
+
+        counter.advance(10_000);
+        assert_eq!(counter.count_last(128), 0);
+        assert_eq!(counter.count_last(u64::MAX), 0);
+        assert_eq!(counter.lifetime_count(), 2);
+
+        // Going back in time is ignored
+        counter.advance(50);
+        assert_eq!(counter.count_last(128), 0);
     }
 }
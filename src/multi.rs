@@ -0,0 +1,216 @@
+use alloc::boxed::Box;
+use core::sync::atomic::AtomicU64;
+
+use crate::{check_geometry, ring_range, valid_range, window_size, Slot, ORDERINGS};
+
+/// `K` counters of different kinds of events, e.g. hits, misses and errors, sharing one
+/// sliding window.
+///
+/// Each kind has its own ring of 2^`slot_count_exp` slots of 2^`slot_size_exp` time units, laid
+/// out like an [`InvocationCounter`](crate::InvocationCounter), but all kinds share a single
+/// latest observed time. A registration of any kind advances the window of every kind, so the
+/// kinds stay time-aligned and their old intervals are evicted together, whereas separate
+/// counters would each keep the window of their own latest registration.
+///
+/// Kinds are numbered `0..K`, e.g. by casting a fieldless enum to `usize`.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::MultiCounter;
+/// const HIT: usize = 0;
+/// const MISS: usize = 1;
+///
+/// // 8 slots × 16 time units = 128 time unit sliding window, for 2 kinds
+/// let counter = MultiCounter::<2>::new(3, 4);
+///
+/// counter.register(HIT, 10);
+/// counter.register(HIT, 12);
+/// counter.register(MISS, 20);
+///
+/// assert_eq!(counter.count_in(HIT, 0, 32), 2);
+/// assert_eq!(counter.count_in(MISS, 0, 32), 1);
+///
+/// counter.register(HIT, 1_000); // Moves the window of both kinds
+/// assert_eq!(counter.count_in(MISS, 0, 32), 0);
+/// ```
+#[derive(Debug)]
+pub struct MultiCounter<const K: usize> {
+    kinds: [Box<[Slot]>; K],
+    slot_count_exp: u8,
+    slot_size_exp: u8,
+    /// `2^slot_count_exp - 1`, used to map an interval to its slot with a mask
+    slot_count_mask: u64,
+    max_current_time: AtomicU64,
+}
+
+impl<const K: usize> MultiCounter<K> {
+    /// Creates a new `MultiCounter` with 2^`slot_count_exp` slots of 2^`slot_size_exp` time
+    /// units for each of the `K` kinds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is zero, or if the configuration is rejected by
+    /// [`InvocationCounter::try_new`](crate::InvocationCounter::try_new).
+    pub fn new(slot_count_exp: u8, slot_size_exp: u8) -> Self {
+        assert!(K > 0, "MultiCounter requires at least one kind");
+        check_geometry(slot_count_exp, slot_size_exp).unwrap_or_else(|error| {
+            panic!("MultiCounter::new({slot_count_exp}, {slot_size_exp}): {error}")
+        });
+
+        Self {
+            kinds: core::array::from_fn(|_| {
+                (0..1usize << slot_count_exp).map(|_| Slot::new()).collect()
+            }),
+            slot_count_exp,
+            slot_size_exp,
+            slot_count_mask: (1u64 << slot_count_exp) - 1,
+            max_current_time: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers an invocation of `kind` at the specified time, advancing the window of every
+    /// kind.
+    ///
+    /// Like [`InvocationCounter::register`](crate::InvocationCounter::register), a timestamp
+    /// whose interval already left the window is dropped. This method is thread-safe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kind` isn't below `K`.
+    pub fn register(&self, kind: usize, current_time: u64) {
+        let orderings = &ORDERINGS.register;
+        let slots = &self.kinds[kind];
+
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        let interval_start = current_time >> self.slot_size_exp << self.slot_size_exp;
+        if interval_start
+            < ring_range(current_max_time, self.slot_size_exp, self.window_size()).start
+        {
+            return;
+        }
+
+        let slot_index = (current_time >> self.slot_size_exp) & self.slot_count_mask;
+        slots[slot_index as usize].register(orderings, interval_start, 1);
+
+        self.max_current_time
+            .fetch_max(current_time, orderings.max_time_update_success);
+    }
+
+    /// Returns the total number of invocations of `kind` within the specified time range.
+    ///
+    /// Behaves exactly like [`InvocationCounter::count_in`](crate::InvocationCounter::count_in):
+    /// `start_time` is inclusive, `end_time` is exclusive, both are aligned to slot boundaries,
+    /// only slots within the shared window are considered, and the result saturates at
+    /// `u32::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kind` isn't below `K`.
+    pub fn count_in(&self, kind: usize, start_time: u64, end_time: u64) -> u32 {
+        let orderings = &ORDERINGS.count_in;
+        let slots = &self.kinds[kind];
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        let valid_range = valid_range(
+            current_max_time,
+            self.slot_size_exp,
+            self.window_size(),
+            start_time,
+            end_time,
+        );
+
+        let mut count = 0u32;
+        for slot in slots.iter() {
+            let (time_in_slot, slot_count) = slot.load(orderings);
+            if valid_range.contains(&time_in_slot) {
+                count = count.saturating_add(slot_count);
+            }
+        }
+
+        count
+    }
+
+    /// Returns the latest time observed by any kind, `0` if none.
+    pub fn max_time(&self) -> u64 {
+        self.max_current_time.load(ORDERINGS.count_in.max_time_load)
+    }
+
+    /// Returns the size of the shared window in time units, saturating at `u64::MAX`.
+    fn window_size(&self) -> u64 {
+        window_size(self.slot_count_exp, self.slot_size_exp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InvocationCounter;
+
+    #[test]
+    fn test_kinds_match_separate_counters() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let multi = MultiCounter::<3>::new(2, 3);
+        let separate: [_; 3] = core::array::from_fn(|_| InvocationCounter::new(2, 3));
+
+        for time in 0..200u64 {
+            let kind = (time * 7 % 3) as usize;
+            multi.register(kind, time);
+            separate[kind].register(time);
+            // The separate counters only see their own registrations: keep their windows
+            // aligned with the shared one
+            for counter in &separate {
+                counter.advance_time(time);
+            }
+        }
+
+        for (kind, counter) in separate.iter().enumerate() {
+            for start in 150..210 {
+                for end in start..210 {
+                    assert_eq!(
+                        multi.count_in(kind, start, end),
+                        counter.count_in(start, end)
+                    );
+                }
+            }
+        }
+        assert_eq!(multi.max_time(), 199);
+    }
+
+    #[test]
+    fn test_kinds_evict_together() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = MultiCounter::<3>::new(2, 3);
+        counter.register(0, 1);
+        counter.register(1, 2);
+        counter.register(1, 10);
+        counter.register(2, 20);
+        assert_eq!(counter.count_in(0, 0, 32), 1);
+        assert_eq!(counter.count_in(1, 0, 32), 2);
+        assert_eq!(counter.count_in(2, 0, 32), 1);
+
+        // A registration of one kind moves the window of all of them
+        counter.register(2, 40); // Window [16, 48)
+        assert_eq!(counter.count_in(0, 0, 48), 0);
+        assert_eq!(counter.count_in(1, 0, 48), 0);
+        assert_eq!(counter.count_in(2, 0, 48), 2);
+
+        // Intervals that left the shared window are dropped for every kind
+        counter.register(0, 5);
+        assert_eq!(counter.count_in(0, 0, 48), 0);
+
+        counter.register(0, 1_000);
+        for kind in 0..3 {
+            assert_eq!(counter.count_in(kind, 0, 1_001), (kind == 0) as u32);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kind_out_of_range() {
+        MultiCounter::<2>::new(2, 3).register(2, 0);
+    }
+}
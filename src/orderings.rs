@@ -0,0 +1,239 @@
+use core::sync::atomic::Ordering;
+
+/// The memory orderings used by the atomic operations of [`register`] and [`count_in`].
+///
+/// [`ORDERINGS`] and [`OrderingProfile::orderings`] are not only documentation: the
+/// implementation reads its orderings from them, so the reports always describe the code that
+/// actually runs, and pinning their values in a test catches any refactor that weakens an
+/// ordering.
+///
+/// [`register`]: crate::InvocationCounter::register
+/// [`count_in`]: crate::InvocationCounter::count_in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderingReport {
+    /// Orderings used when registering invocations
+    pub register: RegisterOrderings,
+    /// Orderings used when querying counts
+    pub count_in: CountInOrderings,
+}
+
+/// The memory orderings used by [`InvocationCounter::register`](crate::InvocationCounter::register).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterOrderings {
+    /// Load of the slot's `interval_start` to decide between incrementing and rolling over
+    pub slot_interval_load: Ordering,
+    /// Compare-and-swap taking the slot's seqlock when rolling over: success ordering
+    pub slot_rollover_success: Ordering,
+    /// Compare-and-swap taking the slot's seqlock when rolling over: failure ordering
+    pub slot_rollover_failure: Ordering,
+    /// Saturating increment of the slot's counter (both the update and the re-read on retry)
+    pub slot_increment: Ordering,
+    /// Swap of the slot's counter with the new count after a rollover
+    pub slot_reset: Ordering,
+    /// Store releasing the slot's seqlock after a rollover
+    pub slot_unlock: Ordering,
+    /// Increment of the lifetime total and of the spillover
+    pub totals_update: Ordering,
+    /// Load of the latest observed time
+    pub max_time_load: Ordering,
+    /// Compare-and-swap advancing the latest observed time: success ordering
+    pub max_time_update_success: Ordering,
+    /// Compare-and-swap advancing the latest observed time: failure ordering
+    pub max_time_update_failure: Ordering,
+}
+
+/// The memory orderings used by [`InvocationCounter::count_in`](crate::InvocationCounter::count_in)
+/// and the other range queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountInOrderings {
+    /// Load of the latest observed time, which determines the valid window
+    pub max_time_load: Ordering,
+    /// Load of each slot's seqlock version, before reading its content
+    pub slot_version_load: Ordering,
+    /// Load of each slot's `interval_start`
+    pub slot_interval_load: Ordering,
+    /// Load of each in-range slot's counter
+    pub slot_counter_load: Ordering,
+}
+
+/// The memory orderings of the default [`OrderingProfile::Strict`] profile.
+///
+/// # Examples
+///
+/// ```rust
+/// # use invocation_counter::ORDERINGS;
+/// # use core::sync::atomic::Ordering;
+/// assert_eq!(ORDERINGS.count_in.slot_counter_load, Ordering::Acquire);
+/// ```
+pub const ORDERINGS: OrderingReport = OrderingReport {
+    register: RegisterOrderings {
+        slot_interval_load: Ordering::Acquire,
+        slot_rollover_success: Ordering::AcqRel,
+        slot_rollover_failure: Ordering::Acquire,
+        slot_increment: Ordering::Relaxed,
+        slot_reset: Ordering::AcqRel,
+        slot_unlock: Ordering::Release,
+        totals_update: Ordering::Relaxed,
+        max_time_load: Ordering::Acquire,
+        max_time_update_success: Ordering::Release,
+        max_time_update_failure: Ordering::Relaxed,
+    },
+    count_in: CountInOrderings {
+        max_time_load: Ordering::Acquire,
+        slot_version_load: Ordering::Acquire,
+        slot_interval_load: Ordering::Acquire,
+        slot_counter_load: Ordering::Acquire,
+    },
+};
+
+/// The memory orderings of [`OrderingProfile::Fast`].
+const FAST_ORDERINGS: OrderingReport = OrderingReport {
+    register: RegisterOrderings {
+        slot_interval_load: Ordering::Relaxed,
+        slot_rollover_success: Ordering::Acquire,
+        slot_rollover_failure: Ordering::Relaxed,
+        slot_increment: Ordering::Relaxed,
+        slot_reset: Ordering::Relaxed,
+        slot_unlock: Ordering::Release,
+        totals_update: Ordering::Relaxed,
+        max_time_load: Ordering::Relaxed,
+        max_time_update_success: Ordering::Relaxed,
+        max_time_update_failure: Ordering::Relaxed,
+    },
+    count_in: CountInOrderings {
+        max_time_load: Ordering::Relaxed,
+        slot_version_load: Ordering::Acquire,
+        slot_interval_load: Ordering::Relaxed,
+        slot_counter_load: Ordering::Relaxed,
+    },
+};
+
+/// Selects the memory orderings a counter uses, see
+/// [`InvocationCounter::with_ordering_profile`](crate::InvocationCounter::with_ordering_profile).
+///
+/// Both profiles keep the slot seqlock sound: taking it is always `Acquire`, releasing it is
+/// always `Release`, and readers always validate its version with an `Acquire` load and fence,
+/// so a query never pairs an interval with another interval's count. They differ in the
+/// orderings around it:
+///
+/// | Operation                                         | `Strict`             | `Fast`              |
+/// |---------------------------------------------------|----------------------|---------------------|
+/// | Slot `interval_start` load (register and queries) | `Acquire`            | `Relaxed`           |
+/// | Slot counter load (queries)                       | `Acquire`            | `Relaxed`           |
+/// | Seqlock acquisition on rollover (success/failure) | `AcqRel`/`Acquire`   | `Acquire`/`Relaxed` |
+/// | Counter swap on rollover                          | `AcqRel`             | `Relaxed`           |
+/// | Latest observed time load (register and queries)  | `Acquire`            | `Relaxed`           |
+/// | Latest observed time update (success/failure)     | `Release`/`Relaxed`  | `Relaxed`/`Relaxed` |
+///
+/// Both use `Relaxed` for counter increments and the lifetime and spillover totals, `Release`
+/// to release the seqlock and `Acquire` to load its version.
+///
+/// With `Fast`, a query doesn't synchronize with the registrations it observes: it may see a
+/// newer latest observed time together with slightly older slot contents, so the window it
+/// counts can lag behind registrations happening concurrently by a few invocations. Once
+/// registrations stop, both profiles return the same counts. On x86 every load is already an
+/// acquire load, so the profiles compile to nearly the same code; on weakly-ordered
+/// architectures such as ARM, `Fast` avoids the barriers of acquire loads and release stores.
+///
+/// The profile covers registrations and queries, including
+/// [`max_time`](crate::InvocationCounter::max_time). Operations that reset a counter or move
+/// state between counters, such as [`advance_time`](crate::InvocationCounter::advance_time),
+/// [`merge`](crate::InvocationCounter::merge),
+/// [`swap_contents`](crate::InvocationCounter::swap_contents), cloning and serialization,
+/// always use acquire/release orderings for the latest observed time, whatever the profile.
+/// [`StaticInvocationCounter`](crate::StaticInvocationCounter) and
+/// [`MultiCounter`](crate::MultiCounter) have no profile setting and always use
+/// [`ORDERINGS`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OrderingProfile {
+    /// Acquire/release orderings, so that observing a slot's or the latest time's update also
+    /// observes the writes that preceded it. This is the default.
+    #[default]
+    Strict,
+    /// `Relaxed` orderings wherever the seqlock doesn't need stronger ones, trading some
+    /// accuracy under concurrent registration for throughput.
+    Fast,
+}
+
+impl OrderingProfile {
+    /// Returns the memory orderings used by counters configured with this profile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::{OrderingProfile, ORDERINGS};
+    /// # use core::sync::atomic::Ordering;
+    /// assert_eq!(OrderingProfile::Strict.orderings(), ORDERINGS);
+    /// assert_eq!(OrderingProfile::Fast.orderings().count_in.slot_counter_load, Ordering::Relaxed);
+    /// ```
+    pub const fn orderings(self) -> OrderingReport {
+        *self.orderings_ref()
+    }
+
+    pub(crate) const fn orderings_ref(self) -> &'static OrderingReport {
+        match self {
+            Self::Strict => &ORDERINGS,
+            Self::Fast => &FAST_ORDERINGS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_orderings() {
+        assert_eq!(
+            ORDERINGS,
+            OrderingReport {
+                register: RegisterOrderings {
+                    slot_interval_load: Ordering::Acquire,
+                    slot_rollover_success: Ordering::AcqRel,
+                    slot_rollover_failure: Ordering::Acquire,
+                    slot_increment: Ordering::Relaxed,
+                    slot_reset: Ordering::AcqRel,
+                    slot_unlock: Ordering::Release,
+                    totals_update: Ordering::Relaxed,
+                    max_time_load: Ordering::Acquire,
+                    max_time_update_success: Ordering::Release,
+                    max_time_update_failure: Ordering::Relaxed,
+                },
+                count_in: CountInOrderings {
+                    max_time_load: Ordering::Acquire,
+                    slot_version_load: Ordering::Acquire,
+                    slot_interval_load: Ordering::Acquire,
+                    slot_counter_load: Ordering::Acquire,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_pinned_fast_orderings() {
+        assert_eq!(
+            OrderingProfile::Fast.orderings(),
+            OrderingReport {
+                register: RegisterOrderings {
+                    slot_interval_load: Ordering::Relaxed,
+                    slot_rollover_success: Ordering::Acquire,
+                    slot_rollover_failure: Ordering::Relaxed,
+                    slot_increment: Ordering::Relaxed,
+                    slot_reset: Ordering::Relaxed,
+                    slot_unlock: Ordering::Release,
+                    totals_update: Ordering::Relaxed,
+                    max_time_load: Ordering::Relaxed,
+                    max_time_update_success: Ordering::Relaxed,
+                    max_time_update_failure: Ordering::Relaxed,
+                },
+                count_in: CountInOrderings {
+                    max_time_load: Ordering::Relaxed,
+                    slot_version_load: Ordering::Acquire,
+                    slot_interval_load: Ordering::Relaxed,
+                    slot_counter_load: Ordering::Relaxed,
+                },
+            }
+        );
+        assert_eq!(OrderingProfile::default().orderings(), ORDERINGS);
+    }
+}
@@ -0,0 +1,83 @@
+use rayon::prelude::*;
+
+use crate::InvocationCounter;
+
+impl InvocationCounter {
+    /// Returns the total number of invocations within the specified time range, summing the
+    /// slots on the rayon thread pool.
+    ///
+    /// Returns exactly what [`count_in`](Self::count_in) returns for the same arguments, but
+    /// splits the slots across threads and sums their contributions with a parallel fold. This
+    /// only pays off for very wide counters, with hundreds of thousands of slots, queried over
+    /// most of their window: for anything smaller, the serial `count_in` is faster.
+    ///
+    /// Only available with the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(16, 0); // 65536 slots × 1 unit
+    ///
+    /// for time in 0..1000 {
+    ///     counter.register(time);
+    /// }
+    ///
+    /// assert_eq!(counter.count_in_par(0, 500), 500);
+    /// assert_eq!(counter.count_in_par(0, 500), counter.count_in(0, 500));
+    /// ```
+    pub fn count_in_par(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let valid_range = self.valid_range(start_time, end_time);
+        if valid_range.is_empty() {
+            return 0;
+        }
+
+        let count: u64 = self
+            .slots
+            .par_iter()
+            .map(|slot| slot.load(&self.orderings().count_in))
+            .filter(|(interval_start, _)| valid_range.contains(interval_start))
+            .map(|(_, count)| count as u64)
+            .sum();
+
+        u32::try_from(count).unwrap_or(u32::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_in_par_matches_count_in() {
+        // 4096 slots (2^12) * 4 time units (2^2) = 16384 time units window
+        let counter = InvocationCounter::new(12, 2);
+        for time in (0..40_000).step_by(7) {
+            counter.register_n(time, (time % 5) as u32);
+        }
+
+        for (start, end) in [
+            (0, u64::MAX),
+            (0, 30_000),
+            (23_617, 23_618),
+            (25_000, 39_999),
+            (39_990, 80_000),
+            (30_000, 30_000),
+            (31_000, 30_000),
+        ] {
+            assert_eq!(
+                counter.count_in_par(start, end),
+                counter.count_in(start, end),
+                "[{start}, {end})"
+            );
+        }
+
+        // Saturates at u32::MAX like count_in
+        counter.register_n(39_996, u32::MAX);
+        assert_eq!(counter.count_in_par(0, u64::MAX), u32::MAX);
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::string::String;
+use alloc::vec;
+use core::fmt::Write;
+
+use crate::InvocationCounter;
+
+impl InvocationCounter {
+    /// Renders the current window in the Prometheus text exposition format.
+    ///
+    /// Two metric families are written, both gauges:
+    ///
+    /// - `{name}_window_count`: the number of invocations in the window, like
+    ///   [`total`](Self::total).
+    /// - `{name}_bucket`: one sample per slot, labelled `le` with an age in time units, counting
+    ///   the invocations of the intervals at most that old. Ages are measured from the end of
+    ///   the interval containing the latest observed time, so `le="{slot_size}"` is the latest
+    ///   interval and `le="{window_size}"` the whole window, followed by `le="+Inf"`. Like the
+    ///   buckets of a Prometheus histogram, they are cumulative.
+    ///
+    /// The label values only depend on the geometry, so every scrape returns the same series
+    /// in the same order. The output has one line per slot: keep it for counters with a
+    /// reasonable number of slots.
+    ///
+    /// Only available with the `metrics` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid Prometheus metric name, matching
+    /// `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::InvocationCounter;
+    /// let counter = InvocationCounter::new(1, 4); // 2 slots × 16 units = 32-unit window
+    ///
+    /// counter.register(10);
+    /// counter.register(20);
+    /// counter.register(21);
+    ///
+    /// let output = counter.export("api_calls");
+    /// assert_eq!(
+    ///     output.lines().collect::<Vec<_>>(),
+    ///     [
+    ///         "# TYPE api_calls_window_count gauge",
+    ///         "api_calls_window_count 3",
+    ///         "# TYPE api_calls_bucket gauge",
+    ///         "api_calls_bucket{le=\"16\"} 2",
+    ///         "api_calls_bucket{le=\"32\"} 3",
+    ///         "api_calls_bucket{le=\"+Inf\"} 3",
+    ///     ]
+    /// );
+    /// ```
+    pub fn export(&self, name: &str) -> String {
+        assert!(
+            is_metric_name(name),
+            "invalid Prometheus metric name {name:?}"
+        );
+
+        let ring_range = self.ring_range();
+        let latest_interval = (ring_range.end - 1) >> self.slot_size_exp;
+        let mut by_age = vec![0u64; self.slots.len()];
+        self.scan_in(ring_range.start, ring_range.end, |interval_start, count| {
+            by_age[(latest_interval - (interval_start >> self.slot_size_exp)) as usize] +=
+                count as u64;
+        });
+        let total: u64 = by_age.iter().sum();
+
+        // Writing into a `String` can't fail
+        let mut output = String::new();
+        let _ = writeln!(output, "# TYPE {name}_window_count gauge");
+        let _ = writeln!(output, "{name}_window_count {total}");
+        let _ = writeln!(output, "# TYPE {name}_bucket gauge");
+        let mut cumulative = 0;
+        for (age, count) in by_age.iter().enumerate() {
+            cumulative += count;
+            let le = (age as u128 + 1) << self.slot_size_exp;
+            let _ = writeln!(output, "{name}_bucket{{le=\"{le}\"}} {cumulative}");
+        }
+        let _ = writeln!(output, "{name}_bucket{{le=\"+Inf\"}} {total}");
+
+        output
+    }
+}
+
+/// Returns `true` if `name` matches `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+fn is_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_' || first == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a sample line into its metric name, `le` label and value.
+    fn parse_sample(line: &str) -> (&str, Option<&str>, u64) {
+        let (series, value) = line.rsplit_once(' ').unwrap();
+        let value = value.parse().unwrap();
+        match series.split_once('{') {
+            Some((name, labels)) => {
+                let le = labels
+                    .strip_prefix("le=\"")
+                    .and_then(|labels| labels.strip_suffix("\"}"))
+                    .unwrap();
+                (name, Some(le), value)
+            }
+            None => (series, None, value),
+        }
+    }
+
+    #[test]
+    fn test_export() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for time in [1, 2, 17, 25, 26, 27] {
+            counter.register(time);
+        }
+
+        let output = counter.export("requests");
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("# TYPE requests_window_count gauge"));
+        assert_eq!(
+            parse_sample(lines.next().unwrap()),
+            ("requests_window_count", None, 6)
+        );
+        assert_eq!(lines.next(), Some("# TYPE requests_bucket gauge"));
+        let buckets: Vec<_> = lines.map(parse_sample).collect();
+        assert_eq!(
+            buckets,
+            [
+                ("requests_bucket", Some("8"), 3),
+                ("requests_bucket", Some("16"), 4),
+                ("requests_bucket", Some("24"), 4),
+                ("requests_bucket", Some("32"), 6),
+                ("requests_bucket", Some("+Inf"), 6),
+            ]
+        );
+
+        // The same series are exported as time moves on
+        counter.advance_time(40);
+        let output = counter.export("requests");
+        let buckets: Vec<_> = output.lines().skip(3).map(parse_sample).collect();
+        assert_eq!(
+            buckets,
+            [
+                ("requests_bucket", Some("8"), 0),
+                ("requests_bucket", Some("16"), 0),
+                ("requests_bucket", Some("24"), 3),
+                ("requests_bucket", Some("32"), 4),
+                ("requests_bucket", Some("+Inf"), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metric_name() {
+        assert!(is_metric_name("api_calls"));
+        assert!(is_metric_name(":ns:calls_2"));
+        assert!(!is_metric_name(""));
+        assert!(!is_metric_name("2xx"));
+        assert!(!is_metric_name("api-calls"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid Prometheus metric name")]
+    fn test_export_invalid_name() {
+        InvocationCounter::new(2, 3).export("api calls");
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{CounterSnapshot, InvocationCounter};
+
+/// Serializes the window data of the counter as a [`CounterSnapshot`].
+///
+/// Only available with the `serde` feature.
+impl Serialize for InvocationCounter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CounterSnapshot::capture(self).serialize(serializer)
+    }
+}
+
+/// Deserializes a [`CounterSnapshot`] and [restores](CounterSnapshot::restore) it.
+///
+/// Only available with the `serde` feature.
+impl<'de> Deserialize<'de> for InvocationCounter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CounterSnapshot::deserialize(deserializer)?
+            .restore()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        // 8 slots (2^3) * 4 time units (2^2) = 32 time units window
+        let counter = InvocationCounter::new(3, 2);
+        for time in [0, 1, 3, 9, 17, 25, 30, 33, 41] {
+            counter.register(time);
+        }
+
+        let json = serde_json::to_string(&counter).unwrap();
+        let restored: InvocationCounter = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.slot_count_exp(), 3);
+        assert_eq!(restored.slot_size_exp(), 2);
+        for start in 0..64 {
+            for end in start..64 {
+                assert_eq!(restored.count_in(start, end), counter.count_in(start, end));
+            }
+        }
+
+        // The restored counter keeps working
+        counter.register(45);
+        restored.register(45);
+        assert_eq!(restored.count(), counter.count());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_inconsistent_data() {
+        let json = r#"{"slot_count_exp":1,"slot_size_exp":2,"max_current_time":0,"slots":[[0,1]]}"#;
+        let error = serde_json::from_str::<InvocationCounter>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid counter data: expected 2^1 slots, found 1"
+        );
+    }
+}
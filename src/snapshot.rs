@@ -0,0 +1,187 @@
+use alloc::boxed::Box;
+use core::sync::atomic::Ordering;
+
+use crate::{valid_range, window_size, CounterError, InvocationCounter};
+
+/// An immutable, point-in-time copy of an [`InvocationCounter`].
+///
+/// A snapshot answers the same range queries as the counter it was captured from, as they
+/// would have been answered at capture time. Taking snapshots periodically and comparing them
+/// with [`count_in_delta`](Self::count_in_delta) gives per-range rates of change without any
+/// external bookkeeping.
+///
+/// Capturing reads each slot's interval and count as a consistent pair, but slots one after
+/// the other, so a snapshot taken while other threads are registering is a best-effort view
+/// rather than a transactional copy.
+///
+/// With the `serde` feature, snapshots implement `Serialize` and `Deserialize`, and
+/// [`restore`](Self::restore) turns a deserialized snapshot back into a live counter.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::{CounterSnapshot, InvocationCounter};
+/// let counter = InvocationCounter::new(3, 4);
+///
+/// counter.register(10);
+/// let before = CounterSnapshot::capture(&counter);
+///
+/// counter.register(12);
+/// counter.register(20);
+/// let after = CounterSnapshot::capture(&counter);
+///
+/// assert_eq!(before.count_in(0, 32), 1);
+/// assert_eq!(after.count_in(0, 32), 3);
+/// assert_eq!(after.count_in_delta(&before, 0, 32), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CounterSnapshot {
+    slot_count_exp: u8,
+    slot_size_exp: u8,
+    max_current_time: u64,
+    /// `(interval_start, counter)` for every slot, in ring order
+    slots: Box<[(u64, u32)]>,
+}
+
+impl CounterSnapshot {
+    /// Captures the current state of `counter`.
+    pub fn capture(counter: &InvocationCounter) -> Self {
+        let max_current_time = counter.max_current_time.load(Ordering::Acquire);
+        let slots = counter
+            .slots
+            .iter()
+            .map(|slot| slot.load(&counter.orderings().count_in))
+            .collect();
+
+        Self {
+            slot_count_exp: counter.slot_count_exp,
+            slot_size_exp: counter.slot_size_exp,
+            max_current_time,
+            slots,
+        }
+    }
+
+    /// Builds a live counter holding the captured data.
+    ///
+    /// The restored counter answers queries like the captured one did at capture time.
+    /// Settings, the lifetime count and the spillover aren't part of a snapshot and start
+    /// from their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::Decode`] if the snapshot is inconsistent, which can only happen
+    /// if it was deserialized from tampered data: the number of slots doesn't match the slot
+    /// count exponent, or a slot holds an interval that can't belong to it.
+    pub fn restore(&self) -> Result<InvocationCounter, CounterError> {
+        InvocationCounter::from_parts(
+            self.slot_count_exp,
+            self.slot_size_exp,
+            self.max_current_time,
+            self.slots.iter().copied(),
+        )
+    }
+
+    /// Returns the slot count exponent of the captured counter.
+    pub fn slot_count_exp(&self) -> u8 {
+        self.slot_count_exp
+    }
+
+    /// Returns the slot size exponent of the captured counter.
+    pub fn slot_size_exp(&self) -> u8 {
+        self.slot_size_exp
+    }
+
+    /// Returns the total number of invocations within the specified time range at capture
+    /// time.
+    ///
+    /// Follows the same rules as [`InvocationCounter::count_in`]: `start_time` is inclusive,
+    /// `end_time` is exclusive, both are aligned to slot boundaries, only slots within the
+    /// window ending at the latest time observed before capture are considered, and the result
+    /// saturates at `u32::MAX`.
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let valid_range = valid_range(
+            self.max_current_time,
+            self.slot_size_exp,
+            window_size(self.slot_count_exp, self.slot_size_exp),
+            start_time,
+            end_time,
+        );
+
+        self.slots
+            .iter()
+            .filter(|(interval_start, _)| valid_range.contains(interval_start))
+            .fold(0, |sum, &(_, count)| sum.saturating_add(count))
+    }
+
+    /// Returns how much the count within the specified time range changed between `prev` and
+    /// this snapshot.
+    ///
+    /// This is `self.count_in(start, end) - prev.count_in(start, end)`. A negative value means
+    /// that, between the two snapshots, more invocations in the range were evicted (because
+    /// their slots were reused or they aged out of the window) than were registered.
+    pub fn count_in_delta(&self, prev: &CounterSnapshot, start_time: u64, end_time: u64) -> i64 {
+        self.count_in(start_time, end_time) as i64 - prev.count_in(start_time, end_time) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_matches_counter() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        for t in [1, 3, 9, 17, 25, 30] {
+            counter.register(t);
+        }
+
+        let snapshot = CounterSnapshot::capture(&counter);
+        assert_eq!(snapshot.slot_count_exp(), 2);
+        assert_eq!(snapshot.slot_size_exp(), 3);
+        for start in 0..40 {
+            for end in start..40 {
+                assert_eq!(snapshot.count_in(start, end), counter.count_in(start, end));
+            }
+        }
+
+        // Later registrations don't affect the snapshot
+        counter.register(31);
+        assert_eq!(snapshot.count_in(24, 32), 2);
+        assert_eq!(counter.count_in(24, 32), 3);
+
+        let restored = snapshot.restore().unwrap();
+        assert_eq!(CounterSnapshot::capture(&restored), snapshot);
+    }
+
+    #[test]
+    fn test_count_in_delta() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window
+        let counter = InvocationCounter::new(2, 3);
+        counter.register(1);
+        counter.register(2);
+        counter.register(10);
+        let first = CounterSnapshot::capture(&counter);
+
+        counter.register(11);
+        counter.register(12);
+        counter.register(20);
+        let second = CounterSnapshot::capture(&counter);
+
+        assert_eq!(second.count_in_delta(&first, 0, 32), 3);
+        assert_eq!(second.count_in_delta(&first, 8, 16), 2);
+        assert_eq!(second.count_in_delta(&first, 0, 8), 0);
+        assert_eq!(first.count_in_delta(&second, 0, 32), -3);
+
+        // Time moves on: [0, 8) and [8, 16) are evicted
+        counter.register(40);
+        let third = CounterSnapshot::capture(&counter);
+        assert_eq!(third.count_in_delta(&second, 0, 16), -5);
+        assert_eq!(third.count_in_delta(&second, 40, 48), 1);
+    }
+}
@@ -0,0 +1,226 @@
+use core::sync::atomic::AtomicU64;
+
+use crate::{ring_range, valid_range, Slot, ORDERINGS};
+
+/// An [`InvocationCounter`](crate::InvocationCounter) whose slots are stored inline.
+///
+/// The ring buffer is a `[Slot; N]` array rather than a heap-allocated slice, so constructing
+/// and querying the counter never touches the heap, and for small `N` the compiler can fully
+/// unroll the summation loop in [`count_in`](Self::count_in).
+///
+/// Each slot covers 2^`slot_size_exp` time units, so the sliding window spans
+/// `N × 2^slot_size_exp` time units. The interval starting at `interval_start` is stored in
+/// slot `(interval_start >> slot_size_exp) % N`; when `N` is a power of two this is exactly the
+/// layout of an `InvocationCounter` with `N` slots, and both counters return identical results.
+/// Any other `N` works too: intervals still cycle through the slots in order, at the cost of a
+/// division instead of a mask on every registration.
+///
+/// # Example
+///
+/// ```rust
+/// # use invocation_counter::StaticInvocationCounter;
+/// // 8 slots × 16 time units = 128 time unit sliding window
+/// let counter = StaticInvocationCounter::<8>::new(4);
+///
+/// counter.register(10);
+/// counter.register(25);
+///
+/// assert_eq!(counter.count_in(0, 26), 2);
+/// assert_eq!(counter.count_in(16, 32), 1);
+/// ```
+#[derive(Debug)]
+pub struct StaticInvocationCounter<const N: usize> {
+    slots: [Slot; N],
+    slot_size_exp: u8,
+    max_current_time: AtomicU64,
+}
+
+impl<const N: usize> StaticInvocationCounter<N> {
+    /// Creates a new `StaticInvocationCounter` with `N` slots of 2^`slot_size_exp` time units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero or if `slot_size_exp` is 64 or more, which
+    /// [`InvocationCounter::try_new`](crate::InvocationCounter::try_new) rejects as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use invocation_counter::StaticInvocationCounter;
+    /// // 16 slots, each covering 4 time units (2^2)
+    /// let counter = StaticInvocationCounter::<16>::new(2);
+    /// ```
+    pub fn new(slot_size_exp: u8) -> Self {
+        assert!(N > 0, "StaticInvocationCounter requires at least one slot");
+        assert!(
+            slot_size_exp < 64,
+            "StaticInvocationCounter::new({slot_size_exp}): slot_size_exp must be below 64"
+        );
+
+        Self {
+            slots: core::array::from_fn(|_| Slot::new()),
+            slot_size_exp,
+            max_current_time: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers an invocation at the specified time.
+    ///
+    /// Like [`InvocationCounter::register`](crate::InvocationCounter::register), a timestamp
+    /// whose interval already left the window is dropped. This method is thread-safe. Multiple
+    /// threads can call this method concurrently without external synchronization.
+    pub fn register(&self, current_time: u64) {
+        let orderings = &ORDERINGS.register;
+
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        let interval_index = current_time >> self.slot_size_exp;
+        let interval_start = interval_index << self.slot_size_exp;
+        if interval_start
+            < ring_range(current_max_time, self.slot_size_exp, self.window_size()).start
+        {
+            return;
+        }
+
+        let slot_index = interval_index % N as u64;
+
+        self.slots[slot_index as usize].register(orderings, interval_start, 1);
+
+        self.max_current_time
+            .fetch_max(current_time, orderings.max_time_update_success);
+    }
+
+    /// Returns the total number of invocations within the specified time range.
+    ///
+    /// Behaves exactly like [`InvocationCounter::count_in`](crate::InvocationCounter::count_in):
+    /// `start_time` is inclusive, `end_time` is exclusive, both are aligned to slot boundaries,
+    /// and only slots within the current window are considered.
+    #[inline]
+    pub fn count_in(&self, start_time: u64, end_time: u64) -> u32 {
+        if start_time >= end_time {
+            return 0;
+        }
+
+        let orderings = ORDERINGS.count_in;
+
+        let current_max_time = self.max_current_time.load(orderings.max_time_load);
+        let valid_range = valid_range(
+            current_max_time,
+            self.slot_size_exp,
+            self.window_size(),
+            start_time,
+            end_time,
+        );
+
+        let mut count = 0;
+        for slot in &self.slots {
+            let (time_in_slot, slot_count) = slot.load(&orderings);
+            if valid_range.contains(&time_in_slot) {
+                count = slot_count.saturating_add(count);
+            }
+        }
+
+        count
+    }
+
+    /// Returns the total number of invocations within the last `window` time units, ending at
+    /// the latest observed time.
+    ///
+    /// Behaves exactly like
+    /// [`InvocationCounter::count_last`](crate::InvocationCounter::count_last): `window` is
+    /// clamped to the `N × 2^slot_size_exp` window of this counter.
+    pub fn count_last(&self, window: u64) -> u32 {
+        let current_max_time = self.max_current_time.load(ORDERINGS.count_in.max_time_load);
+        let end_time = current_max_time.saturating_add(1);
+        let window = window.min(self.window_size());
+
+        self.count_in(end_time.saturating_sub(window), end_time)
+    }
+
+    /// Returns the slot size exponent used to create this counter.
+    pub fn slot_size_exp(&self) -> u8 {
+        self.slot_size_exp
+    }
+
+    /// Returns the `N × 2^slot_size_exp` time units covered by the window, saturating at
+    /// `u64::MAX`.
+    fn window_size(&self) -> u64 {
+        (N as u64).saturating_mul(1 << self.slot_size_exp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InvocationCounter;
+
+    #[test]
+    fn test_matches_boxed_counter() {
+        // 16 slots (2^4) * 4 time units (2^2) = 64 time units window
+        let fixed = StaticInvocationCounter::<16>::new(2);
+        let boxed = InvocationCounter::new(4, 2);
+
+        let mut time = 0;
+        for i in 0..500u64 {
+            // Irregular but deterministic steps, sometimes jumping past the window
+            time += (i * 7919) % 13 + if i % 97 == 0 { 80 } else { 0 };
+            fixed.register(time);
+            boxed.register(time);
+
+            for window in [0, 1, 4, 5, 30, 64, 65, u64::MAX] {
+                assert_eq!(fixed.count_last(window), boxed.count_last(window));
+            }
+            for start in (time.saturating_sub(80)..time + 8).step_by(3) {
+                for end in [
+                    start,
+                    start + 1,
+                    start + 5,
+                    start + 17,
+                    start + 64,
+                    time + 1,
+                ] {
+                    assert_eq!(
+                        fixed.count_in(start, end),
+                        boxed.count_in(start, end),
+                        "mismatch for [{start}, {end}) at time {time}"
+                    );
+                }
+            }
+        }
+
+        // A stale timestamp arriving out of order must not overwrite a live slot
+        let fixed = StaticInvocationCounter::<4>::new(3);
+        let boxed = InvocationCounter::new(2, 3);
+        for time in [32, 0, 9, 31, 8] {
+            fixed.register(time);
+            boxed.register(time);
+        }
+        // Window [8, 40): only 0 is dropped
+        assert_eq!(fixed.count_in(0, u64::MAX), 4);
+        assert_eq!(fixed.count_in(0, 8), 0);
+        assert_eq!(fixed.count_in(0, u64::MAX), boxed.count_in(0, u64::MAX));
+        assert_eq!(fixed.count_last(u64::MAX), boxed.count_last(u64::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "slot_size_exp must be below 64")]
+    fn test_invalid_slot_size_exp() {
+        StaticInvocationCounter::<4>::new(64);
+    }
+
+    #[test]
+    fn test_non_power_of_two_slots() {
+        // 3 slots * 4 time units = 12 time units window
+        let counter = StaticInvocationCounter::<3>::new(2);
+
+        counter.register(0); // slot 0
+        counter.register(4); // slot 1
+        counter.register(8); // slot 2
+        assert_eq!(counter.count_in(0, 12), 3);
+
+        counter.register(12); // wraps to slot 0, evicts [0, 4)
+        assert_eq!(counter.count_in(0, 4), 0);
+        assert_eq!(counter.count_in(0, 16), 3);
+        assert_eq!(counter.count_last(u64::MAX), 3);
+        assert_eq!(counter.count_last(5), 2);
+    }
+}
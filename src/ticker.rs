@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::{AbortHandle, JoinHandle};
+
+use crate::InvocationCounter;
+
+/// Spawns a Tokio task that periodically advances the counter's current time.
+///
+/// Every `interval`, the task calls [`InvocationCounter::advance_time`] with the value returned
+/// by `now_fn`, so idle counters keep aging even when nothing is registered. Without it,
+/// window-relative queries would keep reporting stale intervals during quiet periods.
+///
+/// `now_fn` must return timestamps in the same time unit the counter is registered with.
+///
+/// The task runs until it is aborted. Both a [`JoinHandle`] and an [`AbortHandle`] are
+/// returned, so the task can be stopped from a place that doesn't own the join handle.
+///
+/// This function must be called from within a Tokio runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use std::time::{Duration, Instant};
+/// # use invocation_counter::{spawn_ticker, InvocationCounter};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let counter = Arc::new(InvocationCounter::new(6, 10)); // milliseconds
+/// let start = Instant::now();
+///
+/// let (_join, abort) = spawn_ticker(
+///     Arc::clone(&counter),
+///     Duration::from_secs(1),
+///     move || start.elapsed().as_millis() as u64,
+/// );
+///
+/// // ...
+///
+/// abort.abort();
+/// # }
+/// ```
+pub fn spawn_ticker<F>(
+    counter: Arc<InvocationCounter>,
+    interval: Duration,
+    now_fn: F,
+) -> (JoinHandle<()>, AbortHandle)
+where
+    F: Fn() -> u64 + Send + 'static,
+{
+    let handle = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            counter.advance_time(now_fn());
+        }
+    });
+    let abort = handle.abort_handle();
+
+    (handle, abort)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ticker_advances_window() {
+        // 4 slots (2^2) * 8 time units (2^3) = 32 time units window, in milliseconds
+        let counter = Arc::new(InvocationCounter::new(2, 3));
+        let start = Instant::now();
+
+        counter.register(0);
+        assert_eq!(counter.count_in(0, 8), 1);
+
+        let (join, abort) =
+            spawn_ticker(Arc::clone(&counter), Duration::from_millis(10), move || {
+                start.elapsed().as_millis() as u64
+            });
+
+        // Still within the window
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        assert_eq!(counter.count_in(0, 8), 1);
+
+        // Well past the window: the registration at time 0 has aged out
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(counter.count_in(0, 8), 0);
+
+        abort.abort();
+        assert!(join.await.unwrap_err().is_cancelled());
+    }
+}
@@ -0,0 +1,130 @@
+//! Property-based tests checking invariants that must hold for every geometry and every
+//! sequence of registrations.
+//!
+//! The suite runs with a fixed seed so failures are reproducible. To explore more cases,
+//! raise the case count through the environment:
+//!
+//! ```sh
+//! PROPTEST_CASES=10000 cargo test --test properties
+//! ```
+
+use invocation_counter::InvocationCounter;
+use proptest::prelude::*;
+use proptest::test_runner::RngSeed;
+
+fn config() -> ProptestConfig {
+    ProptestConfig {
+        rng_seed: RngSeed::Fixed(0x1c0_c0de),
+        ..ProptestConfig::default()
+    }
+}
+
+/// `(slot_count_exp, slot_size_exp, times)` with small geometries so that windows wrap often.
+fn scenario() -> impl Strategy<Value = (u8, u8, Vec<u64>)> {
+    (0u8..=5, 0u8..=5, prop::collection::vec(0u64..2_000, 1..200))
+}
+
+fn window_size(counter: &InvocationCounter) -> u64 {
+    (1 << counter.slot_count_exp()) * (1 << counter.slot_size_exp())
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn count_in_is_monotonic_in_end((slot_count_exp, slot_size_exp, times) in scenario(), start in 0u64..2_000) {
+        let counter = InvocationCounter::new(slot_count_exp, slot_size_exp);
+        for &time in &times {
+            counter.register(time);
+        }
+
+        let mut previous = 0;
+        for end in start..start + 300 {
+            let count = counter.count_in(start, end);
+            prop_assert!(count >= previous, "count_in({start}, {end}) = {count} < {previous}");
+            previous = count;
+        }
+    }
+
+    #[test]
+    fn count_in_never_exceeds_registered((slot_count_exp, slot_size_exp, times) in scenario(), start in 0u64..2_000, len in 0u64..2_000) {
+        let counter = InvocationCounter::new(slot_count_exp, slot_size_exp);
+        for (registered, &time) in times.iter().enumerate() {
+            counter.register(time);
+            prop_assert!(counter.count_in(start, start + len) as usize <= registered + 1);
+            prop_assert!(counter.count_in(0, 4_096) as usize <= registered + 1);
+        }
+    }
+
+    #[test]
+    fn nothing_before_the_window_is_counted((slot_count_exp, slot_size_exp, times) in scenario()) {
+        let counter = InvocationCounter::new(slot_count_exp, slot_size_exp);
+        let window = window_size(&counter);
+
+        let mut max_time = 0;
+        for &time in &times {
+            counter.register(time);
+            max_time = max_time.max(time);
+
+            let window_start = (max_time + 1).saturating_sub(window);
+            prop_assert_eq!(
+                counter.count_in(0, max_time + 1),
+                counter.count_in(window_start, max_time + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn in_order_registrations_match_a_model((slot_count_exp, slot_size_exp, mut times) in scenario(), start in 0u64..2_000, len in 0u64..500) {
+        times.sort_unstable();
+
+        let counter = InvocationCounter::new(slot_count_exp, slot_size_exp);
+        for &time in &times {
+            counter.register(time);
+        }
+
+        // With non-decreasing timestamps no slot is ever clobbered, so the counter is exact
+        // up to slot alignment within the window
+        let slot_size = 1u64 << slot_size_exp;
+        let max_time = *times.last().unwrap();
+        let ring_end = (max_time / slot_size + 1) * slot_size;
+        let ring_start = ring_end.saturating_sub(window_size(&counter));
+        let end = start + len;
+        let aligned_start = start / slot_size * slot_size;
+        let aligned_end = end.div_ceil(slot_size) * slot_size;
+
+        let expected = if start >= end {
+            0
+        } else {
+            times
+                .iter()
+                .map(|time| time / slot_size * slot_size)
+                .filter(|interval| (ring_start..ring_end).contains(interval))
+                .filter(|interval| (aligned_start..aligned_end).contains(interval))
+                .count()
+        };
+
+        prop_assert_eq!(counter.count_in(start, end) as usize, expected);
+    }
+
+    #[test]
+    fn evicted_data_never_reappears((slot_count_exp, slot_size_exp, mut times) in scenario()) {
+        times.sort_unstable();
+
+        let counter = InvocationCounter::new(slot_count_exp, slot_size_exp);
+        let slot_size = 1u64 << slot_size_exp;
+        let window = window_size(&counter);
+
+        let mut seen_intervals = Vec::new();
+        for &time in &times {
+            counter.register(time);
+            seen_intervals.push(time / slot_size * slot_size);
+
+            let ring_start = (time / slot_size + 1) * slot_size;
+            let ring_start = ring_start.saturating_sub(window);
+            for &interval in seen_intervals.iter().filter(|interval| **interval < ring_start) {
+                prop_assert_eq!(counter.count_in(interval, interval + slot_size), 0);
+            }
+        }
+    }
+}